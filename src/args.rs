@@ -1,16 +1,251 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::schema::reserved::ReservedPolicy;
+use crate::text::Format;
+use crate::vhdl::Bus;
 
 #[derive(Parser, Debug)]
-#[command(version, about="Convert spreadsheets register maps to IP-XACT XML files.", long_about = None)]
+#[command(
+    version,
+    about = "Convert spreadsheets register maps to IP-XACT XML files.",
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Args {
-    /// Path to the input excel file.
-    #[arg(short, long)]
-    pub input: String,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    // `irgen -i ...` without a subcommand is `irgen generate -i ...`
+    #[command(flatten)]
+    pub generate: GenerateArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Write IP-XACT and the other outputs.
+    Generate(GenerateArgs),
+    /// Build every output irgen.toml turns on without writing anything.
+    Check(InputArgs),
+    /// Compare the register maps of two inputs.
+    Diff(DiffArgs),
+    /// Check the VERSION bump between two inputs and write release notes.
+    Changelog(ChangelogArgs),
+    /// Turn an IP-XACT file into a YAML, JSON or TOML register map.
+    Import(ImportArgs),
+    /// List the sheets of a workbook or CSV directory and what irgen makes of
+    /// them.
+    ListSheets(ListSheetsArgs),
+    /// Write the parsed register map as JSON, YAML or TOML.
+    DumpModel(DumpModelArgs),
+    /// List the input and output formats.
+    Formats,
+}
+
+impl Command {
+    pub fn config(&self) -> Option<&ConfigArgs> {
+        match self {
+            Command::Generate(args) => Some(&args.input.config),
+            Command::Check(args) => Some(&args.config),
+            Command::Diff(args) => Some(&args.config),
+            Command::Changelog(args) => Some(&args.config),
+            Command::Import(args) => Some(&args.config),
+            Command::ListSheets(args) => Some(&args.config),
+            Command::DumpModel(args) => Some(&args.input.config),
+            Command::Formats => None,
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ConfigArgs {
+    /// Project settings to use instead of the nearest irgen.toml.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Don't look for an irgen.toml.
+    #[arg(long, conflicts_with = "config")]
+    pub no_config: bool,
+}
+
+/// Options that decide what register map is built.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ModelArgs {
+    /// How reserved fields end up in the outputs [default: drop]
+    #[arg(long, value_enum)]
+    pub reserved: Option<ReservedPolicy>,
+
+    /// Extra regex for reserved field names, may be repeated.
+    #[arg(long = "reserved-pattern", value_name = "REGEX")]
+    pub reserved_patterns: Vec<String>,
+
+    /// TOML file adding or replacing ATTRIBUTE codes.
+    #[arg(long, value_name = "FILE")]
+    pub attributes: Option<String>,
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct InputArgs {
+    /// Path to the input workbook (xlsx, xls, ods, ...), a directory of
+    /// CSV/TSV sheets, the CSV/TSV sheets themselves, or a YAML, JSON or TOML
+    /// register map. Taken from irgen.toml if not given.
+    #[arg(short, long, num_args = 1..)]
+    pub input: Vec<String>,
+
+    #[command(flatten)]
+    pub model: ModelArgs,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct GenerateArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
 
     /// Path for the output XML file.
     #[arg(short, long)]
     pub output: Option<String>,
 
+    /// Skip the IP-XACT XML, for builds that only want the other outputs.
+    #[arg(long)]
+    pub no_ipxact: bool,
+
+    /// Spaces per level in the XML, 0 for a single line [default: 2]
+    #[arg(long, value_name = "N")]
+    pub indent: Option<usize>,
+
+    /// Start the XML with a comment naming irgen and the input.
+    #[arg(long)]
+    pub xml_comment: bool,
+
+    /// Add the time of the run to the XML comment, SOURCE_DATE_EPOCH if set.
+    /// Implies --xml-comment.
+    #[arg(long)]
+    pub timestamp: bool,
+
     #[clap(long)]
     pub regvue: bool,
+
+    /// Path for the RegVue JSON file, next to the XML if not given. Implies
+    /// --regvue.
+    #[arg(long, value_name = "FILE")]
+    pub regvue_output: Option<String>,
+
+    /// Also write a C header with block base addresses and register offsets.
+    #[arg(long, value_name = "FILE")]
+    pub header: Option<String>,
+
+    /// Also write a `no_std` Rust crate with typed register blocks into DIR.
+    #[arg(long, value_name = "DIR")]
+    pub rust: Option<String>,
+
+    /// Also write a Python register model with a pluggable bus backend.
+    #[arg(long, value_name = "FILE")]
+    pub python: Option<String>,
+
+    /// Also write VHDL-2008 register banks and their package into DIR.
+    #[arg(long, value_name = "DIR")]
+    pub vhdl: Option<String>,
+
+    /// The bus of the VHDL register banks [default: axi4-lite]
+    #[arg(long, value_enum, value_name = "BUS")]
+    pub vhdl_bus: Option<Bus>,
+
+    /// Also write the parsed register map as JSON, YAML or TOML, by extension.
+    #[arg(long, value_name = "FILE")]
+    pub emit_model: Option<String>,
+
+    /// Generate again whenever the input, irgen.toml or the attributes file
+    /// changes.
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Write nothing, exit with 1 if an output differs from what it would be.
+    #[arg(long, conflicts_with = "watch")]
+    pub check: bool,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct DiffArgs {
+    /// The older input.
+    pub old: String,
+
+    /// The newer input.
+    pub new: String,
+
+    /// Print the changes as JSON.
+    #[arg(long)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub model: ModelArgs,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ChangelogArgs {
+    /// The previous release.
+    pub old: String,
+
+    /// The release to write notes for.
+    pub new: String,
+
+    /// Markdown file for the release notes, standard output if not given.
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    #[command(flatten)]
+    pub model: ModelArgs,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ImportArgs {
+    /// IP-XACT 1685-2014 XML file.
+    #[arg(short, long)]
+    pub input: String,
+
+    /// YAML, JSON or TOML file to write, by extension.
+    #[arg(short, long)]
+    pub output: String,
+
+    /// TOML file adding or replacing ATTRIBUTE codes.
+    #[arg(long, value_name = "FILE")]
+    pub attributes: Option<String>,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ListSheetsArgs {
+    /// Workbook, directory of CSV/TSV sheets, or the sheets themselves. Taken
+    /// from irgen.toml if not given.
+    #[arg(short, long, num_args = 1..)]
+    pub input: Vec<String>,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct DumpModelArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// File to write, standard output if not given.
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Format of the model, taken from the extension of --output if not given.
+    #[arg(long, value_enum)]
+    pub format: Option<Format>,
 }
@@ -0,0 +1,201 @@
+use crate::diff::{Change, Kind};
+use crate::error::Error;
+
+/// How much VERSION has to go up for a set of changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Bump {
+    /// Breaking changes need a major version, additions a minor one and
+    /// anything else a patch.
+    pub fn required(changes: &[Change]) -> Self {
+        changes
+            .iter()
+            .map(|change| match change.kind {
+                _ if change.breaking => Bump::Major,
+                Kind::Added => Bump::Minor,
+                _ => Bump::Patch,
+            })
+            .max()
+            .unwrap_or(Bump::None)
+    }
+
+    /// The bump from `old` to `new`, both `MAJOR.MINOR[.PATCH]`. Before 1.0 a
+    /// new minor version counts as a major one.
+    pub fn between(old: &str, new: &str) -> anyhow::Result<Self, Error> {
+        let (old, new) = (parse_version(old)?, parse_version(new)?);
+        Ok(if new <= old {
+            Bump::None
+        } else if new[0] > old[0] || (old[0] == 0 && new[1] > old[1]) {
+            Bump::Major
+        } else if new[1] > old[1] {
+            Bump::Minor
+        } else {
+            Bump::Patch
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Bump::None => "no",
+            Bump::Patch => "a patch",
+            Bump::Minor => "a minor",
+            Bump::Major => "a major",
+        }
+    }
+}
+
+fn parse_version(version: &str) -> anyhow::Result<[u64; 3], Error> {
+    let invalid = || Error::Input(format!("VERSION `{}` is not MAJOR.MINOR[.PATCH]", version));
+    let parts = version
+        .trim()
+        .trim_start_matches(['v', 'V'])
+        .split('.')
+        .map(|part| part.parse::<u64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    match parts[..] {
+        [major, minor] => Ok([major, minor, 0]),
+        [major, minor, patch] => Ok([major, minor, patch]),
+        _ => Err(invalid()),
+    }
+}
+
+/// Why going from VERSION `old` to `new` doesn't fit `changes`, if it doesn't.
+pub fn check_version(
+    old: &str,
+    new: &str,
+    changes: &[Change],
+) -> anyhow::Result<Option<String>, Error> {
+    let (required, bump) = (Bump::required(changes), Bump::between(old, new)?);
+    Ok((bump < required).then(|| {
+        format!(
+            "VERSION {} -> {} is {} bump, the changes need {} version",
+            old,
+            new,
+            bump.name(),
+            required.name()
+        )
+    }))
+}
+
+/// A Markdown section for the release notes of `name` `version`.
+pub fn release_notes(name: &str, old_version: &str, version: &str, changes: &[Change]) -> String {
+    let mut notes = format!("## {} {}\n\n", name, version);
+    if changes.is_empty() {
+        notes.push_str(&format!("No register changes since {}.\n", old_version));
+        return notes;
+    }
+
+    let breaking: Vec<&Change> = changes.iter().filter(|change| change.breaking).collect();
+    let (added, other): (Vec<&Change>, Vec<&Change>) = changes
+        .iter()
+        .filter(|change| !change.breaking)
+        .partition(|change| change.kind == Kind::Added);
+    if breaking.is_empty() {
+        notes.push_str(&format!(
+            "Compatible with {}, software written for it keeps working.\n",
+            old_version
+        ));
+    } else {
+        notes.push_str(&format!(
+            "Software written for {} may need changes.\n",
+            old_version
+        ));
+    }
+
+    for (title, changes) in [
+        ("Breaking changes", breaking),
+        ("Additions", added),
+        ("Other changes", other),
+    ] {
+        if changes.is_empty() {
+            continue;
+        }
+        notes.push_str(&format!("\n### {}\n\n", title));
+        for change in changes {
+            notes.push_str(&format!(
+                "- {} `{}`{}\n",
+                change.item(),
+                change.path,
+                change.detail()
+            ));
+        }
+    }
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::Item;
+
+    fn change(kind: Kind, breaking: bool) -> Change {
+        Change {
+            item: Item::Register,
+            path: "blk.reg".into(),
+            kind,
+            domain: None,
+            old: None,
+            new: None,
+            breaking,
+        }
+    }
+
+    #[test]
+    fn required_takes_the_largest_bump() {
+        assert_eq!(Bump::required(&[]), Bump::None);
+        assert_eq!(
+            Bump::required(&[change(Kind::Description, false)]),
+            Bump::Patch
+        );
+        assert_eq!(
+            Bump::required(&[change(Kind::Description, false), change(Kind::Added, false)]),
+            Bump::Minor
+        );
+        // an addition over an old register breaks software
+        assert_eq!(
+            Bump::required(&[change(Kind::Added, false), change(Kind::Added, true)]),
+            Bump::Major
+        );
+        assert_eq!(Bump::required(&[change(Kind::Reset, true)]), Bump::Major);
+    }
+
+    #[test]
+    fn between_compares_versions() {
+        assert_eq!(Bump::between("1.2", "1.2.0").unwrap(), Bump::None);
+        assert_eq!(Bump::between("1.2.3", "1.2.1").unwrap(), Bump::None);
+        assert_eq!(Bump::between("1.2", "1.2.1").unwrap(), Bump::Patch);
+        assert_eq!(Bump::between("1.2.3", "1.3").unwrap(), Bump::Minor);
+        assert_eq!(Bump::between("1.9", "2.0").unwrap(), Bump::Major);
+        assert_eq!(Bump::between("v1.0", "V1.1").unwrap(), Bump::Minor);
+    }
+
+    #[test]
+    fn between_counts_a_minor_bump_before_1_0_as_major() {
+        assert_eq!(Bump::between("0.3", "0.4").unwrap(), Bump::Major);
+        assert_eq!(Bump::between("0.3", "0.3.1").unwrap(), Bump::Patch);
+        assert_eq!(Bump::between("0.9", "1.0").unwrap(), Bump::Major);
+    }
+
+    #[test]
+    fn between_rejects_other_versions() {
+        assert!(Bump::between("1", "1.1").is_err());
+        assert!(Bump::between("1.0", "1.0.0.1").is_err());
+        assert!(Bump::between("1.0", "1.0-rc1").is_err());
+    }
+
+    #[test]
+    fn check_version_explains_a_small_bump() {
+        let changes = [change(Kind::Removed, true)];
+        assert_eq!(check_version("0.3", "0.4", &changes).unwrap(), None);
+        assert_eq!(
+            check_version("1.3", "1.4", &changes).unwrap().as_deref(),
+            Some("VERSION 1.3 -> 1.4 is a minor bump, the changes need a major version")
+        );
+    }
+}
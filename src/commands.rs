@@ -0,0 +1,284 @@
+use std::path::{Path, PathBuf};
+
+use crate::args::{
+    ChangelogArgs, DiffArgs, DumpModelArgs, GenerateArgs, ImportArgs, ListSheetsArgs,
+};
+use crate::error::{Error, exit};
+use crate::schema::attr::AttributeTable;
+use crate::schema::base::{Component, Register};
+use crate::schema::{ipxact, regvue};
+use crate::{changelog, csv, diff, header, import, input, output, python, rust, text, vhdl};
+
+pub fn generate(args: &GenerateArgs) -> anyhow::Result<u8, Error> {
+    let (compo, attrs) = input::load(&args.input.input, &args.input.model)?;
+    let outputs = outputs(args, &compo, &attrs)?;
+
+    if args.check {
+        let stale: Vec<_> = outputs
+            .iter()
+            .filter(|(text, path)| !output::is_current(path, text))
+            .collect();
+        for (_, path) in &stale {
+            tracing::error!("{} is out of date", path.display());
+        }
+        return Ok(if stale.is_empty() {
+            exit::OK
+        } else {
+            exit::DIFFERENT
+        });
+    }
+
+    for (text, path) in outputs {
+        output::write(&path, &text)?;
+    }
+    Ok(exit::OK)
+}
+
+/// Every output `args` turn on and the file it goes to.
+fn outputs(
+    args: &GenerateArgs,
+    compo: &Component,
+    attrs: &AttributeTable,
+) -> anyhow::Result<Vec<(String, PathBuf)>, Error> {
+    let source = Path::new(&args.input.input[0]);
+
+    let xml_file = args
+        .output
+        .as_deref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            if source.is_dir() {
+                source.join(compo.name()).with_extension("xml")
+            } else if csv::is_csv(source) {
+                source.with_file_name(compo.name()).with_extension("xml")
+            } else {
+                source.with_extension("xml")
+            }
+        });
+    let json_file = args
+        .regvue_output
+        .as_deref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| xml_file.with_extension("json"));
+    let regvue = args.regvue || args.regvue_output.is_some();
+
+    if regvue && json_file == source {
+        return Err(Error::Input(format!(
+            "the RegVue output would overwrite {}, pick another --output",
+            source.display()
+        )));
+    }
+
+    // made all at once, so nothing is written unless every output could be
+    let mut outputs = Vec::new();
+    // the model exactly as the writers get it
+    if let Some(model_file) = &args.emit_model {
+        let model_file = PathBuf::from(model_file);
+        outputs.push((text::to_string_for(compo, &model_file)?, model_file));
+    }
+    if let Some(header_file) = &args.header {
+        outputs.push((header::to_string(compo)?, PathBuf::from(header_file)));
+    }
+    if let Some(dir) = &args.rust {
+        for (text, file) in rust::to_files(compo, attrs)? {
+            outputs.push((text, Path::new(dir).join(file)));
+        }
+    }
+    if let Some(python_file) = &args.python {
+        outputs.push((python::to_string(compo, attrs)?, PathBuf::from(python_file)));
+    }
+    if let Some(dir) = &args.vhdl {
+        let bus = args.vhdl_bus.unwrap_or_default();
+        for (text, file) in vhdl::to_files(compo, attrs, bus)? {
+            outputs.push((text, Path::new(dir).join(file)));
+        }
+    }
+    if !args.no_ipxact {
+        // the time of the run would make every check fail
+        if args.check && args.timestamp && std::env::var_os("SOURCE_DATE_EPOCH").is_none() {
+            return Err(Error::Input(
+                "--check with --timestamp needs SOURCE_DATE_EPOCH".into(),
+            ));
+        }
+        let ipxact_compo = ipxact::Component::try_from((compo, attrs))?;
+        let comment = (args.xml_comment || args.timestamp).then(|| {
+            // file names only, so the XML doesn't depend on where irgen ran
+            let inputs: Vec<_> = args
+                .input
+                .input
+                .iter()
+                .map(|input| {
+                    Path::new(input)
+                        .file_name()
+                        .map_or_else(|| input.clone(), |name| name.to_string_lossy().into_owned())
+                })
+                .collect();
+            let mut comment = format!(
+                "  Generated by irgen {} from {}",
+                env!("CARGO_PKG_VERSION"),
+                inputs.join(" ")
+            );
+            if args.timestamp {
+                comment.push_str(&format!("\n  on {}", output::timestamp()));
+            }
+            comment
+        });
+        let xml = ipxact_compo.to_xml(args.indent.unwrap_or(2), comment.as_deref())?;
+        outputs.push((xml, xml_file));
+    }
+    if regvue {
+        let regvue_doc = regvue::Document::try_from((compo, attrs))?;
+        outputs.push((serde_json::to_string_pretty(&regvue_doc)?, json_file));
+    }
+    Ok(outputs)
+}
+
+/// Everything `generate` with the outputs of irgen.toml does short of writing
+/// files. The IP-XACT and RegVue documents are made even if they are off.
+pub fn check(args: &GenerateArgs) -> anyhow::Result<u8, Error> {
+    let (compo, attrs) = input::load(&args.input.input, &args.input.model)?;
+    compo.validate(&attrs)?;
+    outputs(args, &compo, &attrs)?;
+    ipxact::Component::try_from((&compo, &attrs))?;
+    regvue::Document::try_from((&compo, &attrs))?;
+
+    let regs: Vec<&Register> = compo
+        .blks()
+        .iter()
+        .flat_map(|blk| {
+            blk.regs()
+                .iter()
+                .chain(blk.rfs().iter().flat_map(|rf| rf.regs()))
+        })
+        .collect();
+    let fields: usize = regs.iter().map(|reg| reg.fields().len()).sum();
+    println!(
+        "{}: ok, {} blocks, {} registers, {} fields",
+        args.input.input.join(" "),
+        compo.blks().len(),
+        regs.len(),
+        fields
+    );
+    Ok(exit::OK)
+}
+
+pub fn diff(args: &DiffArgs) -> anyhow::Result<u8, Error> {
+    let (old, attrs) = input::load(std::slice::from_ref(&args.old), &args.model)?;
+    let (new, _) = input::load(std::slice::from_ref(&args.new), &args.model)?;
+    let changes = diff::diff(&old, &new, &attrs)?;
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&diff::Report { changes: &changes })?
+        );
+    } else {
+        for change in &changes {
+            println!("{}", change);
+        }
+    }
+    Ok(if changes.is_empty() {
+        exit::OK
+    } else {
+        exit::DIFFERENT
+    })
+}
+
+/// Release notes for `new`, and whether its VERSION was bumped enough.
+pub fn changelog(args: &ChangelogArgs) -> anyhow::Result<u8, Error> {
+    let (old, attrs) = input::load(std::slice::from_ref(&args.old), &args.model)?;
+    let (new, _) = input::load(std::slice::from_ref(&args.new), &args.model)?;
+    let changes = diff::diff(&old, &new, &attrs)?;
+
+    let notes = changelog::release_notes(new.name(), old.version(), new.version(), &changes);
+    match &args.output {
+        Some(path) => output::write(Path::new(path), &notes)?,
+        None => print!("{}", notes),
+    }
+
+    match changelog::check_version(old.version(), new.version(), &changes)? {
+        Some(problem) => {
+            tracing::error!("{}", problem);
+            Ok(exit::DIFFERENT)
+        }
+        None => Ok(exit::OK),
+    }
+}
+
+pub fn import(args: &ImportArgs) -> anyhow::Result<u8, Error> {
+    let attrs = AttributeTable::load(args.attributes.as_deref().map(Path::new))?;
+    let compo = import::read_ipxact(Path::new(&args.input), &attrs)?;
+    text::write_model(&compo, Path::new(&args.output))?;
+    Ok(exit::OK)
+}
+
+pub fn list_sheets(args: &ListSheetsArgs) -> anyhow::Result<u8, Error> {
+    let inputs: Vec<PathBuf> = args.input.iter().map(PathBuf::from).collect();
+    let (df_map, _) = input::read_sheets(&inputs)?;
+
+    // blocks are whatever the address map names
+    let blocks: Vec<String> = df_map
+        .get("address_map")
+        .and_then(|df| df.column("BLOCK").ok()?.str().ok().cloned())
+        .map(|names| names.into_iter().flatten().map(String::from).collect())
+        .unwrap_or_default();
+
+    let mut names: Vec<&String> = df_map.keys().collect();
+    names.sort();
+    let width = names
+        .iter()
+        .map(|name| name.len())
+        .max()
+        .unwrap_or_default();
+    for name in names {
+        let role = match name.as_str() {
+            "version" => "component",
+            "address_map" => "blocks",
+            "soc" => "IP instances",
+            name if blocks.iter().any(|blk| blk == name) => "block",
+            _ => "unused",
+        };
+        println!(
+            "{:width$}  {:>5} rows  {}",
+            name,
+            df_map[name].height(),
+            role
+        );
+    }
+    Ok(exit::OK)
+}
+
+pub fn dump_model(args: &DumpModelArgs) -> anyhow::Result<u8, Error> {
+    let (compo, _) = input::load(&args.input.input, &args.input.model)?;
+    let output = args.output.as_deref().map(Path::new);
+    let format = args
+        .format
+        .or_else(|| output.and_then(text::format))
+        .unwrap_or(text::Format::Json);
+    let text = text::to_string(&compo, format)?;
+    match output {
+        Some(path) => output::write(path, &text)?,
+        None => print!("{}", text),
+    }
+    Ok(exit::OK)
+}
+
+pub fn formats() -> anyhow::Result<u8, Error> {
+    println!("Inputs:");
+    println!(
+        "  xlsx xlsm xlsb xls ods   workbook with version, address_map or soc, and block sheets"
+    );
+    println!("  csv tsv                  one file per sheet, or a directory of them");
+    println!("  yaml json toml           register map");
+    println!("  xml                      IP-XACT 1685-2014, with `irgen import`");
+    println!();
+    println!("Outputs:");
+    println!("  xml                      IP-XACT 1685-2014");
+    println!("  json                     RegVue, with --regvue");
+    println!("  h                        C header, with --header");
+    println!("  rs                       no_std Rust crate, with --rust");
+    println!("  py                       Python register model, with --python");
+    println!("  vhd                      VHDL-2008 register banks, with --vhdl");
+    println!("  yaml json toml           register map, with --emit-model or `irgen dump-model`");
+    Ok(exit::OK)
+}
@@ -0,0 +1,250 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::args::{ConfigArgs, GenerateArgs, InputArgs, ModelArgs};
+use crate::error::Error;
+use crate::schema::reserved::ReservedPolicy;
+use crate::vhdl::Bus;
+
+pub const FILE_NAME: &str = "irgen.toml";
+
+/// The project settings of an `irgen.toml`. Paths are relative to the file,
+/// options given on the command line win.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    input: Vec<String>,
+    attributes: Option<String>,
+    #[serde(default)]
+    reserved: ReservedConfig,
+    #[serde(default)]
+    ipxact: Ipxact,
+    regvue: Option<Generator>,
+    header: Option<Generator>,
+    rust: Option<Generator>,
+    python: Option<Generator>,
+    vhdl: Option<Vhdl>,
+    model: Option<Generator>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReservedConfig {
+    policy: Option<ReservedPolicy>,
+    #[serde(default)]
+    patterns: Vec<String>,
+}
+
+/// A `[regvue]`-like table turns its output on unless `enabled = false`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Generator {
+    #[serde(default = "enabled")]
+    enabled: bool,
+    output: Option<String>,
+}
+
+/// `[vhdl]`, a `[regvue]`-like table that also picks the bus.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Vhdl {
+    #[serde(default = "enabled")]
+    enabled: bool,
+    output: Option<String>,
+    bus: Option<Bus>,
+}
+
+/// `[ipxact]`, the XML is written unless `enabled = false`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Ipxact {
+    #[serde(default = "enabled")]
+    enabled: bool,
+    output: Option<String>,
+    indent: Option<usize>,
+    #[serde(default)]
+    comment: bool,
+    #[serde(default)]
+    timestamp: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for Ipxact {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            output: None,
+            indent: None,
+            comment: false,
+            timestamp: false,
+        }
+    }
+}
+
+impl Generator {
+    fn output(generator: &Option<Self>) -> Option<&str> {
+        generator
+            .as_ref()
+            .filter(|generator| generator.enabled)
+            .and_then(|generator| generator.output.as_deref())
+    }
+}
+
+/// The nearest `irgen.toml` in `dir` or its parents.
+pub fn discover(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// `--config`, the nearest irgen.toml, or nothing.
+pub fn path(args: Option<&ConfigArgs>) -> anyhow::Result<Option<PathBuf>, Error> {
+    Ok(match args {
+        Some(ConfigArgs {
+            config: Some(path), ..
+        }) => Some(path.clone()),
+        Some(ConfigArgs {
+            no_config: false, ..
+        }) => discover(&std::env::current_dir()?),
+        _ => None,
+    })
+}
+
+impl Config {
+    pub fn load(path: &Path) -> anyhow::Result<Self, Error> {
+        let mut config: Config = toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| Error::Input(format!("{}: {}", path.display(), err)))?;
+
+        let missing = |name: &str, generator: &Option<Generator>| {
+            generator
+                .as_ref()
+                .is_some_and(|generator| generator.enabled && generator.output.is_none())
+                .then(|| Error::Input(format!("{}: [{}] needs an output", path.display(), name)))
+        };
+        if let Some(err) = missing("header", &config.header)
+            .or(missing("rust", &config.rust))
+            .or(missing("python", &config.python))
+            .or(missing("model", &config.model))
+        {
+            return Err(err);
+        }
+        if config
+            .vhdl
+            .as_ref()
+            .is_some_and(|vhdl| vhdl.enabled && vhdl.output.is_none())
+        {
+            return Err(Error::Input(format!(
+                "{}: [vhdl] needs an output",
+                path.display()
+            )));
+        }
+
+        // relative to the file, not to where irgen runs
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let resolve = |file: &mut String| *file = dir.join(&*file).to_string_lossy().into_owned();
+        config.input.iter_mut().for_each(resolve);
+        config.attributes.iter_mut().for_each(resolve);
+        config.ipxact.output.iter_mut().for_each(resolve);
+        for generator in [
+            config.regvue.as_mut(),
+            config.header.as_mut(),
+            config.rust.as_mut(),
+            config.python.as_mut(),
+            config.model.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            generator.output.iter_mut().for_each(resolve);
+        }
+        for vhdl in config.vhdl.iter_mut() {
+            vhdl.output.iter_mut().for_each(resolve);
+        }
+        Ok(config)
+    }
+
+    pub fn input(&self) -> &[String] {
+        &self.input
+    }
+
+    pub fn apply_model(&self, model: &mut ModelArgs) {
+        if model.reserved.is_none() {
+            model.reserved = self.reserved.policy;
+        }
+        if model.reserved_patterns.is_empty() {
+            model.reserved_patterns = self.reserved.patterns.clone();
+        }
+        self.apply_attributes(&mut model.attributes);
+    }
+
+    pub fn apply_attributes(&self, attributes: &mut Option<String>) {
+        if attributes.is_none() {
+            *attributes = self.attributes.clone();
+        }
+    }
+
+    pub fn apply_input(&self, input: &mut InputArgs) {
+        if input.input.is_empty() {
+            input.input = self.input.clone();
+        }
+        self.apply_model(&mut input.model);
+    }
+
+    /// The `generate` run `check` builds the outputs of, without options of
+    /// its own.
+    pub fn generate_args(&self, input: &InputArgs) -> GenerateArgs {
+        let mut args = GenerateArgs {
+            input: input.clone(),
+            ..GenerateArgs::default()
+        };
+        self.apply_generate(&mut args);
+        args
+    }
+
+    pub fn apply_generate(&self, args: &mut GenerateArgs) {
+        self.apply_input(&mut args.input);
+        if args.output.is_none() {
+            args.output = self.ipxact.output.clone();
+        }
+        if !args.no_ipxact {
+            args.no_ipxact = !self.ipxact.enabled;
+        }
+        if args.indent.is_none() {
+            args.indent = self.ipxact.indent;
+        }
+        args.xml_comment |= self.ipxact.comment;
+        args.timestamp |= self.ipxact.timestamp;
+        if args.regvue_output.is_none() {
+            args.regvue_output = Generator::output(&self.regvue).map(String::from);
+        }
+        if !args.regvue {
+            args.regvue = self.regvue.as_ref().is_some_and(|regvue| regvue.enabled);
+        }
+        if args.header.is_none() {
+            args.header = Generator::output(&self.header).map(String::from);
+        }
+        if args.rust.is_none() {
+            args.rust = Generator::output(&self.rust).map(String::from);
+        }
+        if args.python.is_none() {
+            args.python = Generator::output(&self.python).map(String::from);
+        }
+        if let Some(vhdl) = self.vhdl.as_ref().filter(|vhdl| vhdl.enabled) {
+            if args.vhdl.is_none() {
+                args.vhdl = vhdl.output.clone();
+            }
+            if args.vhdl_bus.is_none() {
+                args.vhdl_bus = vhdl.bus;
+            }
+        }
+        if args.emit_model.is_none() {
+            args.emit_model = Generator::output(&self.model).map(String::from);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use polars::prelude::*;
+
+use crate::error::Error;
+
+/// Whether `path` is a sheet this module reads.
+pub fn is_csv(path: &Path) -> bool {
+    separator(path).is_some()
+}
+
+fn separator(path: &Path) -> Option<u8> {
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "csv" => Some(b','),
+        "tsv" => Some(b'\t'),
+        _ => None,
+    }
+}
+
+/// Reads every CSV and TSV file of a directory, see [`read_files`].
+pub fn read_dir(dir: &Path) -> anyhow::Result<HashMap<String, DataFrame>, Error> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+    paths.retain(|path| is_csv(path));
+    paths.sort();
+    read_files(&paths)
+}
+
+/// Reads each file as the sheet named by its stem, `version.csv`,
+/// `address_map.csv` and one `<block>.csv` per block. All cells are read as
+/// strings and empty cells as null, like the cells of a workbook.
+pub fn read_files(paths: &[PathBuf]) -> anyhow::Result<HashMap<String, DataFrame>, Error> {
+    let mut df_map = HashMap::new();
+    for path in paths {
+        let separator = separator(path)
+            .ok_or_else(|| Error::Input(format!("{} is not a CSV or TSV file", path.display())))?;
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| Error::Input(format!("{} has no sheet name", path.display())))?;
+
+        let df = CsvReadOptions::default()
+            .with_has_header(true)
+            .with_infer_schema_length(Some(0))
+            .with_parse_options(CsvParseOptions::default().with_separator(separator))
+            .try_into_reader_with_file_path(Some(path.clone()))?
+            .finish()?;
+
+        if df_map.insert(name.to_owned(), df).is_some() {
+            return Err(Error::Input(format!("sheet `{}` is given twice", name)));
+        }
+    }
+    Ok(df_map)
+}
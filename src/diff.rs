@@ -0,0 +1,744 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::schema::DEFAULT_RESET;
+use crate::schema::attr::AttributeTable;
+use crate::schema::base::{Block, Component, Field, Register, RegisterFile, parse_hex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Item {
+    Block,
+    RegisterFile,
+    Register,
+    Field,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Added,
+    Removed,
+    Renamed,
+    /// a new offset, or new bits for a field
+    Moved,
+    /// a new range, size or count
+    Resized,
+    Access,
+    HwAccess,
+    Volatile,
+    Reserved,
+    Reset,
+    Description,
+}
+
+impl Kind {
+    /// Whether software written for the old map may break, before looking at
+    /// the values.
+    fn breaking(self) -> bool {
+        !matches!(self, Kind::Added | Kind::HwAccess | Kind::Description)
+    }
+}
+
+/// One difference between two register maps. `path` names the item in the
+/// old map, `old` and `new` are the values that changed, if any.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub item: Item,
+    pub path: String,
+    pub kind: Kind,
+    /// The reset domain of a reset change.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<String>,
+    /// Software written for the old map may not work with the new one.
+    pub breaking: bool,
+}
+
+impl Change {
+    pub fn item(&self) -> &str {
+        match self.item {
+            Item::Block => "block",
+            Item::RegisterFile => "register file",
+            Item::Register => "register",
+            Item::Field => "field",
+        }
+    }
+
+    /// What changed, without the item and its path.
+    pub fn detail(&self) -> String {
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "none".into());
+        match self.kind {
+            Kind::Added => format!(": added at {}", value(&self.new)),
+            Kind::Removed => format!(": removed from {}", value(&self.old)),
+            Kind::Renamed => format!(": renamed to {}", value(&self.new)),
+            Kind::Description => ": description changed".into(),
+            kind => {
+                let what = match kind {
+                    Kind::Moved => "moved".into(),
+                    Kind::Resized => "resized".into(),
+                    Kind::Access => "access".into(),
+                    Kind::HwAccess => "hardware access".into(),
+                    Kind::Volatile => "volatile".into(),
+                    Kind::Reserved => "reserved".into(),
+                    _ => format!("{} reset", self.domain.as_deref().unwrap_or(DEFAULT_RESET)),
+                };
+                format!(": {} {} -> {}", what, value(&self.old), value(&self.new))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = match self.kind {
+            Kind::Added => '+',
+            Kind::Removed => '-',
+            _ => '~',
+        };
+        write!(f, "{} {} {}{}", sign, self.item(), self.path, self.detail())?;
+        if self.breaking {
+            write!(f, " (breaking)")?;
+        }
+        Ok(())
+    }
+}
+
+/// The machine-readable form of a diff.
+#[derive(Debug, Serialize)]
+pub struct Report<'a> {
+    pub changes: &'a [Change],
+}
+
+/// The changes from `old` to `new`. Blocks, register files, registers and
+/// fields are matched by name; a block that only changed its name is matched
+/// by its base address or its registers.
+pub fn diff(
+    old: &Component,
+    new: &Component,
+    attrs: &AttributeTable,
+) -> anyhow::Result<Vec<Change>, Error> {
+    let mut diff = Diff {
+        attrs,
+        changes: Vec::new(),
+    };
+
+    let mut added: Vec<&Block> = new
+        .blks()
+        .iter()
+        .filter(|blk| find(old.blks(), blk.name(), Block::name).is_none())
+        .collect();
+    for old_blk in old.blks() {
+        let new_blk = match find(new.blks(), old_blk.name(), Block::name) {
+            Some(new_blk) => new_blk,
+            None => match renamed(old_blk, &added)? {
+                Some(n) => {
+                    let new_blk = added.remove(n);
+                    diff.push(
+                        Item::Block,
+                        old_blk.name(),
+                        Kind::Renamed,
+                        None,
+                        new_blk.name(),
+                    );
+                    new_blk
+                }
+                None => {
+                    diff.push(
+                        Item::Block,
+                        old_blk.name(),
+                        Kind::Removed,
+                        old_blk.offset(),
+                        None,
+                    );
+                    continue;
+                }
+            },
+        };
+        diff.block(old_blk, new_blk)?;
+    }
+    for new_blk in added {
+        diff.push(
+            Item::Block,
+            new_blk.name(),
+            Kind::Added,
+            None,
+            new_blk.offset(),
+        );
+        let span = block_span(new_blk)?;
+        let mut used = false;
+        for old_blk in old.blks() {
+            used |= overlaps(span, block_span(old_blk)?);
+        }
+        diff.mark(used);
+    }
+    Ok(diff.changes)
+}
+
+fn find<'a, T>(items: &'a [T], name: &str, item_name: fn(&T) -> &str) -> Option<&'a T> {
+    items.iter().find(|item| item_name(item) == name)
+}
+
+/// The added block that `blk` was renamed to: one at the same base address,
+/// or one with the same registers.
+fn renamed(blk: &Block, added: &[&Block]) -> anyhow::Result<Option<usize>, Error> {
+    let offset = parse_hex(blk.offset())?;
+    for (n, candidate) in added.iter().enumerate() {
+        if parse_hex(candidate.offset())? == offset {
+            return Ok(Some(n));
+        }
+    }
+    let names = |blk: &Block| {
+        let mut names: Vec<String> = blk.regs().iter().map(|reg| reg.name().to_owned()).collect();
+        names.extend(blk.rfs().iter().map(|rf| rf.name().to_owned()));
+        names.sort();
+        names
+    };
+    let regs = names(blk);
+    Ok(added
+        .iter()
+        .position(|candidate| !regs.is_empty() && names(candidate) == regs))
+}
+
+struct Diff<'a> {
+    attrs: &'a AttributeTable,
+    changes: Vec<Change>,
+}
+
+impl Diff<'_> {
+    fn push<'v>(
+        &mut self,
+        item: Item,
+        path: &str,
+        kind: Kind,
+        old: impl Into<Option<&'v str>>,
+        new: impl Into<Option<&'v str>>,
+    ) {
+        self.changes.push(Change {
+            item,
+            path: path.to_owned(),
+            kind,
+            domain: None,
+            old: old.into().map(String::from),
+            new: new.into().map(String::from),
+            breaking: kind.breaking(),
+        });
+    }
+
+    /// Overrides whether the change just pushed is breaking.
+    fn mark(&mut self, breaking: bool) {
+        if let Some(change) = self.changes.last_mut() {
+            change.breaking = breaking;
+        }
+    }
+
+    /// Records `kind` if the hex numbers `old` and `new` differ.
+    fn hex(
+        &mut self,
+        item: Item,
+        path: &str,
+        kind: Kind,
+        old: &str,
+        new: &str,
+    ) -> anyhow::Result<(), Error> {
+        if parse_hex(old)? != parse_hex(new)? {
+            self.push(item, path, kind, old, new);
+        }
+        Ok(())
+    }
+
+    fn block(&mut self, old: &Block, new: &Block) -> anyhow::Result<(), Error> {
+        let path = old.name();
+        self.hex(Item::Block, path, Kind::Moved, old.offset(), new.offset())?;
+        let (old_range, new_range) = (parse_hex(old.range())?, parse_hex(new.range())?);
+        if old_range != new_range {
+            self.push(Item::Block, path, Kind::Resized, old.range(), new.range());
+            self.mark(new_range < old_range);
+        }
+        self.registers(path, old.regs(), new.regs())?;
+
+        for old_rf in old.rfs() {
+            let rf_path = format!("{}.{}", path, old_rf.name());
+            match find(new.rfs(), old_rf.name(), RegisterFile::name) {
+                Some(new_rf) => self.register_file(&rf_path, old_rf, new_rf)?,
+                None => self.push(
+                    Item::RegisterFile,
+                    &rf_path,
+                    Kind::Removed,
+                    old_rf.offset(),
+                    None,
+                ),
+            }
+        }
+        for new_rf in new.rfs() {
+            if find(old.rfs(), new_rf.name(), RegisterFile::name).is_none() {
+                let rf_path = format!("{}.{}", path, new_rf.name());
+                self.push(
+                    Item::RegisterFile,
+                    &rf_path,
+                    Kind::Added,
+                    None,
+                    new_rf.offset(),
+                );
+                let span = rf_span(new_rf)?;
+                let mut used = false;
+                for old_reg in old.regs() {
+                    used |= overlaps(span, register_span(old_reg)?);
+                }
+                for old_rf in old.rfs() {
+                    used |= overlaps(span, rf_span(old_rf)?);
+                }
+                self.mark(used);
+            }
+        }
+        Ok(())
+    }
+
+    fn register_file(
+        &mut self,
+        path: &str,
+        old: &RegisterFile,
+        new: &RegisterFile,
+    ) -> anyhow::Result<(), Error> {
+        self.hex(
+            Item::RegisterFile,
+            path,
+            Kind::Moved,
+            old.offset(),
+            new.offset(),
+        )?;
+        let (old_dim, new_dim) = (old.dim().parse::<u64>()?, new.dim().parse::<u64>()?);
+        if old_dim != new_dim {
+            self.push(
+                Item::RegisterFile,
+                path,
+                Kind::Resized,
+                old.dim(),
+                new.dim(),
+            );
+            // more instances after the last one keep the old ones in place
+            self.mark(new_dim < old_dim);
+        }
+        self.hex(
+            Item::RegisterFile,
+            path,
+            Kind::Resized,
+            old.range(),
+            new.range(),
+        )?;
+        self.registers(path, old.regs(), new.regs())
+    }
+
+    fn registers(
+        &mut self,
+        parent: &str,
+        old: &[Register],
+        new: &[Register],
+    ) -> anyhow::Result<(), Error> {
+        for old_reg in old {
+            let path = format!("{}.{}", parent, old_reg.name());
+            match find(new, old_reg.name(), Register::name) {
+                Some(new_reg) => self.register(&path, old_reg, new_reg)?,
+                None => self.push(Item::Register, &path, Kind::Removed, old_reg.offset(), None),
+            }
+        }
+        for new_reg in new {
+            if find(old, new_reg.name(), Register::name).is_none() {
+                let path = format!("{}.{}", parent, new_reg.name());
+                self.push(Item::Register, &path, Kind::Added, None, new_reg.offset());
+                // compatible in a hole of the old map
+                let span = register_span(new_reg)?;
+                let mut used = false;
+                for old_reg in old {
+                    used |= overlaps(span, register_span(old_reg)?);
+                }
+                self.mark(used);
+            }
+        }
+        Ok(())
+    }
+
+    fn register(
+        &mut self,
+        path: &str,
+        old: &Register,
+        new: &Register,
+    ) -> anyhow::Result<(), Error> {
+        self.hex(
+            Item::Register,
+            path,
+            Kind::Moved,
+            old.offset(),
+            new.offset(),
+        )?;
+        if old.size() != new.size() {
+            self.push(Item::Register, path, Kind::Resized, old.size(), new.size());
+        }
+
+        // field arrays are compared element by element
+        let flatten = |fields: &[Field]| -> anyhow::Result<Vec<Field>, Error> {
+            Ok(fields
+                .iter()
+                .map(Field::flatten)
+                .collect::<Result<Vec<_>, _>>()?
+                .concat())
+        };
+        let (old_fields, new_fields) = (flatten(old.fields())?, flatten(new.fields())?);
+        for old_field in &old_fields {
+            let field_path = format!("{}.{}", path, old_field.name());
+            match find(&new_fields, old_field.name(), Field::name) {
+                Some(new_field) => self.field(&field_path, old_field, new_field)?,
+                None => {
+                    self.push(
+                        Item::Field,
+                        &field_path,
+                        Kind::Removed,
+                        bits(old_field)?.as_str(),
+                        None,
+                    );
+                    self.mark(!old_field.reserved());
+                }
+            }
+        }
+        for new_field in &new_fields {
+            if find(&old_fields, new_field.name(), Field::name).is_none() {
+                let field_path = format!("{}.{}", path, new_field.name());
+                self.push(
+                    Item::Field,
+                    &field_path,
+                    Kind::Added,
+                    None,
+                    bits(new_field)?.as_str(),
+                );
+                // compatible in bits no old field used, reserved ones included
+                let span = field_span(new_field)?;
+                let mut used = false;
+                for old_field in old_fields.iter().filter(|field| !field.reserved()) {
+                    used |= overlaps(span, field_span(old_field)?);
+                }
+                self.mark(used);
+            }
+        }
+        Ok(())
+    }
+
+    fn field(&mut self, path: &str, old: &Field, new: &Field) -> anyhow::Result<(), Error> {
+        let (old_bits, new_bits) = (bits(old)?, bits(new)?);
+        if old_bits != new_bits {
+            self.push(
+                Item::Field,
+                path,
+                Kind::Moved,
+                old_bits.as_str(),
+                new_bits.as_str(),
+            );
+        }
+        if !old.attr().eq_ignore_ascii_case(new.attr()) {
+            self.push(Item::Field, path, Kind::Access, old.attr(), new.attr());
+        }
+        if old.hw_access() != new.hw_access() {
+            self.push(
+                Item::Field,
+                path,
+                Kind::HwAccess,
+                old.hw_access(),
+                new.hw_access(),
+            );
+        }
+        let (old_volatile, new_volatile) =
+            (old.is_volatile(self.attrs)?, new.is_volatile(self.attrs)?);
+        if old_volatile != new_volatile {
+            let (old_volatile, new_volatile) = (old_volatile.to_string(), new_volatile.to_string());
+            self.push(
+                Item::Field,
+                path,
+                Kind::Volatile,
+                old_volatile.as_str(),
+                new_volatile.as_str(),
+            );
+        }
+        if old.reserved() != new.reserved() {
+            let (old_reserved, new_reserved) =
+                (old.reserved().to_string(), new.reserved().to_string());
+            self.push(
+                Item::Field,
+                path,
+                Kind::Reserved,
+                old_reserved.as_str(),
+                new_reserved.as_str(),
+            );
+            self.mark(new.reserved());
+        }
+
+        // resets are matched by domain, the DEFAULT column is HARD
+        let domain = |domain: Option<&str>| domain.unwrap_or(DEFAULT_RESET).to_owned();
+        let mut domains: Vec<String> = old
+            .resets()
+            .iter()
+            .map(|reset| domain(reset.domain()))
+            .collect();
+        for reset in new.resets() {
+            if !domains.contains(&domain(reset.domain())) {
+                domains.push(domain(reset.domain()));
+            }
+        }
+        for name in domains {
+            let value = |field: &Field| {
+                field
+                    .resets()
+                    .iter()
+                    .find(|reset| domain(reset.domain()) == name)
+                    .map(|reset| match reset.mask() {
+                        Some(mask) => format!("{} mask {}", reset.value(), mask),
+                        None => reset.value().to_owned(),
+                    })
+            };
+            let (old_value, new_value) = (value(old), value(new));
+            let same = match (&old_value, &new_value) {
+                (Some(old_value), Some(new_value)) => same_value(old_value, new_value),
+                _ => old_value == new_value,
+            };
+            if !same {
+                self.changes.push(Change {
+                    item: Item::Field,
+                    path: path.to_owned(),
+                    kind: Kind::Reset,
+                    domain: Some(name),
+                    // a new reset domain changes nothing that was there
+                    breaking: old_value.is_some(),
+                    old: old_value,
+                    new: new_value,
+                });
+            }
+        }
+
+        if old.desc().trim() != new.desc().trim() {
+            self.push(Item::Field, path, Kind::Description, old.desc(), new.desc());
+        }
+        Ok(())
+    }
+}
+
+/// `(start, end)` of what an item occupies, bytes or bits.
+type Span = (u64, u64);
+
+fn overlaps((start, end): Span, (other_start, other_end): Span) -> bool {
+    start < other_end && other_start < end
+}
+
+fn block_span(blk: &Block) -> anyhow::Result<Span, Error> {
+    let offset = parse_hex(blk.offset())?;
+    Ok((offset, offset + parse_hex(blk.range())?))
+}
+
+fn rf_span(rf: &RegisterFile) -> anyhow::Result<Span, Error> {
+    let offset = parse_hex(rf.offset())?;
+    Ok((
+        offset,
+        offset + rf.dim().parse::<u64>()? * parse_hex(rf.range())?,
+    ))
+}
+
+fn register_span(reg: &Register) -> anyhow::Result<Span, Error> {
+    let offset = parse_hex(reg.offset())?;
+    Ok((offset, offset + reg.size().parse::<u64>()? / 8))
+}
+
+fn field_span(field: &Field) -> anyhow::Result<Span, Error> {
+    let offset = field.offset().parse::<u64>()?;
+    Ok((offset, offset + field.width().parse::<u64>()?))
+}
+
+/// `[msb:lsb]` of a single field.
+fn bits(field: &Field) -> anyhow::Result<String, Error> {
+    let offset = field.offset().parse::<u64>()?;
+    let width = field.width().parse::<u64>()?;
+    Ok(format!("[{}:{}]", offset + width - 1, offset))
+}
+
+/// Reset values as numbers where they are, `0x0` and `0x00` are the same.
+fn same_value(old: &str, new: &str) -> bool {
+    let number = |value: &str| {
+        value
+            .split(" mask ")
+            .map(|part| parse_hex(part).ok())
+            .collect::<Option<Vec<_>>>()
+    };
+    match (number(old), number(new)) {
+        (Some(old), Some(new)) => old == new,
+        _ => old == new,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A map with a single block holding `regs`, written as YAML.
+    fn compo(regs: &str) -> Component {
+        serde_norway::from_str(&format!(
+            "{{vendor: v, library: l, name: n, version: '1.0', \
+             blks: [{{name: blk, offset: 0, range: 0x100, regs: {}}}]}}",
+            regs
+        ))
+        .unwrap()
+    }
+
+    fn changes_between(old: &str, new: &str) -> Vec<Change> {
+        let attrs = AttributeTable::load(None).unwrap();
+        diff(&compo(old), &compo(new), &attrs).unwrap()
+    }
+
+    /// The changes from `old` to `new` as `irgen diff` prints them.
+    fn printed(old: &str, new: &str) -> Vec<String> {
+        changes_between(old, new)
+            .iter()
+            .map(Change::to_string)
+            .collect()
+    }
+
+    const CTRL: &str = "{name: ctrl, offset: 0, fields: [\
+        {name: en, offset: 0, width: 1, attr: RW, resets: [{value: 0}]},\
+        {name: mode, offset: 4, width: 4, attr: RW, resets: [{value: 0}], desc: Mode}]}";
+
+    #[test]
+    fn same_map_has_no_changes() {
+        let regs = format!("[{}]", CTRL);
+        assert!(changes_between(&regs, &regs).is_empty());
+    }
+
+    #[test]
+    fn registers_are_matched_by_name() {
+        let old = format!("[{}, {{name: data, offset: 0x4, fields: []}}]", CTRL);
+        let new = format!("[{}, {{name: status, offset: 0x8, fields: []}}]", CTRL);
+        assert_eq!(
+            printed(&old, &new),
+            [
+                "- register blk.data: removed from 0x4 (breaking)",
+                "+ register blk.status: added at 0x8"
+            ]
+        );
+    }
+
+    #[test]
+    fn field_changes() {
+        let old = format!("[{}]", CTRL);
+        let new = "[{name: ctrl, offset: 0, fields: [\
+            {name: en, offset: 0, width: 1, attr: RO, reserved: true, \
+             resets: [{value: 0}, {domain: warm, value: 1}]},\
+            {name: mode, offset: 4, width: 2, attr: RW, resets: [{value: '0x00'}], desc: The mode}]}]";
+        assert_eq!(
+            printed(&old, new),
+            [
+                "~ field blk.ctrl.en: access RW -> RO (breaking)",
+                "~ field blk.ctrl.en: reserved false -> true (breaking)",
+                "~ field blk.ctrl.en: warm reset none -> 0x1",
+                "~ field blk.ctrl.mode: moved [7:4] -> [5:4] (breaking)",
+                "~ field blk.ctrl.mode: description changed",
+            ]
+        );
+    }
+
+    #[test]
+    fn renamed_block_is_matched_by_its_address() {
+        let old = compo(&format!("[{}]", CTRL));
+        let text = serde_norway::to_string(&old)
+            .unwrap()
+            .replace("name: blk", "name: core");
+        let new: Component = serde_norway::from_str(&text).unwrap();
+        let attrs = AttributeTable::load(None).unwrap();
+        let changes: Vec<String> = diff(&old, &new, &attrs)
+            .unwrap()
+            .iter()
+            .map(Change::to_string)
+            .collect();
+        assert_eq!(changes, ["~ block blk: renamed to core (breaking)"]);
+    }
+
+    #[test]
+    fn new_register_in_a_hole_is_compatible() {
+        let old = format!("[{}]", CTRL);
+        let new = format!("[{}, {{name: data, offset: 0x4, fields: []}}]", CTRL);
+        let changes = changes_between(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, Kind::Added);
+        assert_eq!(changes[0].path, "blk.data");
+        assert!(!changes[0].breaking);
+    }
+
+    #[test]
+    fn new_register_over_an_old_one_is_breaking() {
+        let old = format!("[{}]", CTRL);
+        let new = format!(
+            "[{}, {{name: alias, offset: 0x2, size: 16, fields: []}}]",
+            CTRL
+        );
+        let changes = changes_between(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, Kind::Added);
+        assert!(changes[0].breaking);
+    }
+
+    #[test]
+    fn field_becoming_reserved_is_breaking() {
+        let old = format!("[{}]", CTRL);
+        let new = "[{name: ctrl, offset: 0, fields: [\
+            {name: en, offset: 0, width: 1, attr: RW, reserved: true, resets: [{value: 0}]},\
+            {name: mode, offset: 4, width: 4, attr: RW, resets: [{value: 0}], desc: Mode}]}]";
+        let changes = changes_between(&old, new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, Kind::Reserved);
+        assert_eq!(changes[0].path, "blk.ctrl.en");
+        assert!(changes[0].breaking);
+
+        // giving a reserved field a meaning breaks nothing
+        let changes = changes_between(new, &old);
+        assert_eq!(changes.len(), 1);
+        assert!(!changes[0].breaking);
+    }
+
+    #[test]
+    fn changes_are_classified() {
+        let old = format!("[{}]", CTRL);
+        let new = "[{name: ctrl, offset: 0, fields: [\
+            {name: en, offset: 0, width: 1, attr: RO, resets: [{value: 0}, {domain: warm, value: 1}]},\
+            {name: mode, offset: 4, width: 4, attr: RW, resets: [{value: '0x00'}], desc: The mode}]}]";
+        let changes = changes_between(&old, new);
+        let kinds: Vec<(Kind, bool)> = changes
+            .iter()
+            .map(|change| (change.kind, change.breaking))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (Kind::Access, true),
+                // a new reset domain changes nothing that was there
+                (Kind::Reset, false),
+                (Kind::Description, false),
+            ]
+        );
+        assert_eq!(changes[1].domain.as_deref(), Some("warm"));
+    }
+
+    #[test]
+    fn removed_reserved_field_is_compatible() {
+        let old = "[{name: ctrl, offset: 0, fields: [\
+            {name: en, offset: 0, width: 1, attr: RW, resets: [{value: 0}]},\
+            {name: rsvd, offset: 1, width: 7, attr: RO, reserved: true, resets: [{value: 0}]}]}]";
+        let new = "[{name: ctrl, offset: 0, fields: [\
+            {name: en, offset: 0, width: 1, attr: RW, resets: [{value: 0}]}]}]";
+        let changes = changes_between(old, new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, Kind::Removed);
+        assert!(!changes[0].breaking);
+
+        let changes = changes_between(new, old);
+        assert_eq!(changes[0].kind, Kind::Added);
+        assert!(!changes[0].breaking);
+    }
+}
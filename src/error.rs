@@ -1,5 +1,20 @@
+use polars::prelude::PolarsError;
+
 use crate::schema::{ipxact, regvue};
 
+/// Exit codes CI can rely on.
+pub mod exit {
+    pub const OK: u8 = 0;
+    /// `diff` found changes, `changelog` a VERSION not bumped enough or
+    /// `--check` an output that is out of date.
+    pub const DIFFERENT: u8 = 1;
+    // 2 is a bad command line, clap exits with it
+    /// The register description is wrong.
+    pub const INVALID: u8 = 3;
+    /// Files can't be read or written, or irgen itself failed.
+    pub const FAILURE: u8 = 4;
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Calamine error: {0}")]
@@ -9,11 +24,14 @@ pub enum Error {
     Xlsx(#[from] calamine::XlsxError),
 
     #[error("Polars error: {0}")]
-    Polars(#[from] polars::prelude::PolarsError),
+    Polars(#[from] PolarsError),
 
     #[error("XML Serialization error: {0}")]
     XmlSe(#[from] quick_xml::SeError),
 
+    #[error("XML error: {0}")]
+    XmlDe(#[from] quick_xml::DeError),
+
     #[error("Json error: {0}")]
     Json(#[from] serde_json::Error),
 
@@ -23,15 +41,48 @@ pub enum Error {
     #[error("IO error: {0}")]
     IO(#[from] std::io::Error),
 
+    #[error("Input Error: {0}")]
+    Input(String),
+
     #[error("Key Error: Not found for key {0}")]
     NotFound(String),
 
     #[error("Empty Error: {0}")]
     Empty(String),
 
+    #[error("Expansion Error: {1} in \"{0}\"")]
+    Expand(String, String),
+
+    #[error("Cell Error: {0}")]
+    Cell(String),
+
+    #[error("Model Error: {0}")]
+    Model(String),
+
+    #[error("Attribute Error: {0}")]
+    Attribute(String),
+
+    #[error("TOML error: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("TOML Serialization error: {0}")]
+    TomlSe(#[from] toml::ser::Error),
+
+    #[error("Watch error: {0}")]
+    Notify(#[from] notify::Error),
+
+    #[error("YAML error: {0}")]
+    Yaml(#[from] serde_norway::Error),
+
     #[error("IP-XACT Component Error: {0}")]
     IpXactComponent(#[from] ipxact::ComponentBuilderError),
 
+    #[error("IP-XACT ResetTypes Error: {0}")]
+    IpXactResetTypes(#[from] ipxact::ResetTypesBuilderError),
+
+    #[error("IP-XACT ResetType Error: {0}")]
+    IpXactResetType(#[from] ipxact::ResetTypeBuilderError),
+
     #[error("IP-XACT MemoryMaps Error: {0}")]
     IpXactMemoryMaps(#[from] ipxact::MemoryMapsBuilderError),
 
@@ -41,12 +92,24 @@ pub enum Error {
     #[error("IP-XACT Block Error: {0}")]
     IpxactBlock(#[from] ipxact::BlockBuilderError),
 
+    #[error("IP-XACT RegisterFile Error: {0}")]
+    IpxactRegisterFile(#[from] ipxact::RegisterFileBuilderError),
+
     #[error("IP-XACT Register Error: {0}")]
     IpxactRegister(#[from] ipxact::RegisterBuilderError),
 
     #[error("IP-XACT Field Error: {0}")]
     IpxactField(#[from] ipxact::FieldBuilderError),
 
+    #[error("IP-XACT EnumeratedValues Error: {0}")]
+    IpxactEnumeratedValues(#[from] ipxact::EnumeratedValuesBuilderError),
+
+    #[error("IP-XACT EnumeratedValue Error: {0}")]
+    IpxactEnumeratedValue(#[from] ipxact::EnumeratedValueBuilderError),
+
+    #[error("IP-XACT WriteValueConstraint Error: {0}")]
+    IpxactWriteValueConstraint(#[from] ipxact::WriteValueConstraintBuilderError),
+
     #[error("IP-XACT Resets Error: {0}")]
     IpxactResets(#[from] ipxact::ResetsBuilderError),
 
@@ -83,3 +146,42 @@ pub enum Error {
     #[error("ParserInt error: {0}")]
     ParseInt(#[from] std::num::ParseIntError),
 }
+
+impl Error {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            // a workbook or CSV file that can't be opened or decoded
+            Error::Calamine(_)
+            | Error::Xlsx(_)
+            | Error::Polars(PolarsError::IO { .. })
+            | Error::IO(_)
+            | Error::Anyhow(_)
+            | Error::Notify(_)
+            | Error::XmlSe(_)
+            | Error::TomlSe(_)
+            | Error::IpXactComponent(_)
+            | Error::IpXactResetTypes(_)
+            | Error::IpXactResetType(_)
+            | Error::IpXactMemoryMaps(_)
+            | Error::IpXactMemoryMap(_)
+            | Error::IpxactBlock(_)
+            | Error::IpxactRegisterFile(_)
+            | Error::IpxactRegister(_)
+            | Error::IpxactField(_)
+            | Error::IpxactEnumeratedValues(_)
+            | Error::IpxactEnumeratedValue(_)
+            | Error::IpxactWriteValueConstraint(_)
+            | Error::IpxactResets(_)
+            | Error::IpxactReset(_)
+            | Error::RegvueSchema(_)
+            | Error::RegvueLink(_)
+            | Error::RegvueRoot(_)
+            | Error::RegvueEnumValue(_)
+            | Error::RegvueReset(_)
+            | Error::RegvueField(_)
+            | Error::RegvueElement(_)
+            | Error::RegvueDocument(_) => exit::FAILURE,
+            _ => exit::INVALID,
+        }
+    }
+}
@@ -1,11 +1,40 @@
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::fs;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
 
-use calamine::{CellType, DataType, Range};
+use calamine::{
+    CellType, DataType, Range, Reader, Sheets, open_workbook_auto, open_workbook_auto_from_rs,
+};
 use polars::prelude::*;
 
 use crate::error::Error;
 
+/// Reads every worksheet of a xlsx, xlsm, xlsb, xls or ods workbook into a
+/// DataFrame named after the sheet.
+pub fn read_workbook(path: &Path) -> anyhow::Result<HashMap<String, DataFrame>, Error> {
+    match open_workbook_auto(path) {
+        Ok(wb) => read_sheets(wb),
+        // the extension is missing or lies, let the content decide
+        Err(err) => match open_workbook_auto_from_rs(Cursor::new(fs::read(path)?)) {
+            Ok(wb) => read_sheets(wb),
+            Err(_) => Err(err.into()),
+        },
+    }
+}
+
+fn read_sheets<RS: Read + Seek>(
+    mut wb: Sheets<RS>,
+) -> anyhow::Result<HashMap<String, DataFrame>, Error> {
+    wb.worksheets()
+        .iter()
+        .map(|(sheet_name, range_data)| {
+            range_data.to_data_frame().map(|df| (sheet_name.into(), df))
+        })
+        .collect()
+}
+
 pub trait ToDataFrame {
     fn to_data_frame(&self) -> Result<DataFrame, Error>;
 }
@@ -0,0 +1,44 @@
+//! Golden outputs for the generator tests, kept as `testdata/golden/<file>.golden`
+//! so no tool takes them for sources. `IRGEN_BLESS=1 cargo test` writes the
+//! outputs there instead of comparing them.
+
+use std::fs;
+use std::path::Path;
+
+use crate::schema::attr::AttributeTable;
+use crate::schema::base::Component;
+
+/// The register map of `testdata/golden.yaml` and the built-in codes.
+pub fn model() -> (Component, AttributeTable) {
+    let compo = serde_norway::from_str(include_str!("../testdata/golden.yaml")).unwrap();
+    (compo, AttributeTable::load(None).unwrap())
+}
+
+/// Whether `text` is what the golden `file` holds.
+pub fn check(file: &str, text: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("testdata/golden")
+        .join(format!("{}.golden", file));
+    if std::env::var_os("IRGEN_BLESS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, text).unwrap();
+        return;
+    }
+    let golden = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("{}: {}, run with IRGEN_BLESS=1", path.display(), err));
+    // the first line that differs, the whole files are too long to read
+    let line = text
+        .lines()
+        .zip(golden.lines())
+        .position(|(new, old)| new != old)
+        .unwrap_or(text.lines().count().min(golden.lines().count()));
+    assert!(
+        text == golden,
+        "{}:{} differs from the output, run with IRGEN_BLESS=1 if that is intended\n\
+         golden: {:?}\noutput: {:?}",
+        path.display(),
+        line + 1,
+        golden.lines().nth(line),
+        text.lines().nth(line)
+    );
+}
@@ -0,0 +1,82 @@
+use crate::error::Error;
+use crate::schema::base::{Component, parse_hex};
+
+fn ident(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// A C header with the base address of every block and the offsets of its
+/// registers and register files, all prefixed by the component name.
+pub fn to_string(compo: &Component) -> anyhow::Result<String, Error> {
+    let prefix = ident(compo.name());
+    let mut lines = vec![
+        format!(
+            "/* {} {}, generated by irgen */",
+            compo.name(),
+            compo.version()
+        ),
+        format!("#ifndef {}_H", prefix),
+        format!("#define {}_H", prefix),
+    ];
+
+    for blk in compo.blks() {
+        let blk_name = format!("{}_{}", prefix, ident(blk.name()));
+        lines.push(String::new());
+        lines.push(format!(
+            "#define {}_BASE 0x{:X}UL",
+            blk_name,
+            parse_hex(blk.offset())?
+        ));
+        lines.push(format!(
+            "#define {}_SIZE 0x{:X}UL",
+            blk_name,
+            parse_hex(blk.range())?
+        ));
+        for reg in blk.regs() {
+            lines.push(format!(
+                "#define {}_{}_OFFSET 0x{:X}UL",
+                blk_name,
+                ident(reg.name()),
+                parse_hex(reg.offset())?
+            ));
+        }
+        for rf in blk.rfs() {
+            let rf_name = format!("{}_{}", blk_name, ident(rf.name()));
+            lines.push(format!(
+                "#define {}_OFFSET 0x{:X}UL",
+                rf_name,
+                parse_hex(rf.offset())?
+            ));
+            if rf.dim() != "1" {
+                lines.push(format!("#define {}_COUNT {}", rf_name, rf.dim()));
+                lines.push(format!(
+                    "#define {}_STRIDE 0x{:X}UL",
+                    rf_name,
+                    parse_hex(rf.range())?
+                ));
+            }
+            // relative to the register file
+            for reg in rf.regs() {
+                lines.push(format!(
+                    "#define {}_{}_OFFSET 0x{:X}UL",
+                    rf_name,
+                    ident(reg.name()),
+                    parse_hex(reg.offset())?
+                ));
+            }
+        }
+    }
+
+    lines.push(String::new());
+    lines.push(format!("#endif /* {}_H */", prefix));
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
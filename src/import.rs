@@ -0,0 +1,312 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::error::Error;
+use crate::schema::attr::AttributeTable;
+use crate::schema::base::Component;
+
+// The parts of IP-XACT 1685-2014 that have a place in the model. Elements are
+// matched by local name, so the namespace prefix doesn't matter, everything
+// else is skipped.
+
+#[derive(Debug, Deserialize)]
+struct XComponent {
+    vendor: String,
+    library: String,
+    name: String,
+    version: String,
+    #[serde(rename = "memoryMaps", default)]
+    memory_maps: Option<XMemoryMaps>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XMemoryMaps {
+    #[serde(rename = "memoryMap", default)]
+    memory_map: Vec<XMemoryMap>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XMemoryMap {
+    #[serde(rename = "addressBlock", default)]
+    address_block: Vec<XBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XBlock {
+    name: String,
+    #[serde(rename = "baseAddress")]
+    base_address: String,
+    range: String,
+    width: String,
+    #[serde(default)]
+    register: Vec<XRegister>,
+    #[serde(rename = "registerFile", default)]
+    register_file: Vec<XRegisterFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XRegisterFile {
+    name: String,
+    #[serde(default)]
+    dim: Vec<String>,
+    #[serde(rename = "addressOffset")]
+    address_offset: String,
+    range: String,
+    #[serde(default)]
+    register: Vec<XRegister>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XRegister {
+    name: String,
+    #[serde(default)]
+    dim: Vec<String>,
+    #[serde(rename = "addressOffset")]
+    address_offset: String,
+    size: String,
+    #[serde(default)]
+    access: Option<String>,
+    #[serde(default)]
+    field: Vec<XField>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XField {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(rename = "bitOffset")]
+    bit_offset: String,
+    #[serde(rename = "bitWidth")]
+    bit_width: String,
+    #[serde(default)]
+    volatile: Option<bool>,
+    #[serde(default)]
+    access: Option<String>,
+    #[serde(rename = "enumeratedValues", default)]
+    enumerated_values: Option<XEnumeratedValues>,
+    #[serde(rename = "modifiedWriteValue", default)]
+    modified_write_value: Option<String>,
+    #[serde(rename = "readAction", default)]
+    read_action: Option<String>,
+    #[serde(default)]
+    reserved: Option<bool>,
+    #[serde(default)]
+    resets: Option<XResets>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XEnumeratedValues {
+    #[serde(rename = "enumeratedValue", default)]
+    enumerated_value: Vec<XEnumeratedValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XEnumeratedValue {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct XResets {
+    #[serde(default)]
+    reset: Vec<XReset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XReset {
+    #[serde(rename = "@resetTypeRef", default)]
+    reset_type_ref: Option<String>,
+    value: String,
+    #[serde(default)]
+    mask: Option<String>,
+}
+
+/// The largest array `import` reads.
+const MAX_DIM: u64 = 1 << 16;
+
+/// Reads an IP-XACT component into the model. Access, modifiedWriteValue and
+/// readAction are mapped back to ATTRIBUTE codes with `attrs`.
+pub fn read_ipxact(path: &Path, attrs: &AttributeTable) -> anyhow::Result<Component, Error> {
+    let xml: XComponent = quick_xml::de::from_str(&fs::read_to_string(path)?)?;
+
+    let blks = xml
+        .memory_maps
+        .iter()
+        .flat_map(|maps| &maps.memory_map)
+        .flat_map(|map| &map.address_block)
+        .map(|blk| {
+            let rfs = blk
+                .register_file
+                .iter()
+                .map(|rf| {
+                    let path = format!("{}.{}", blk.name, rf.name);
+                    Ok(json!({
+                        "name": rf.name,
+                        "offset": number(&path, &rf.address_offset)?,
+                        "dim": dim(&path, &rf.dim)?,
+                        "range": number(&path, &rf.range)?,
+                        "regs": registers(&path, &rf.register, attrs)?,
+                    }))
+                })
+                .collect::<anyhow::Result<Vec<Value>, Error>>()?;
+            Ok(json!({
+                "name": blk.name,
+                "offset": number(&blk.name, &blk.base_address)?,
+                "range": number(&blk.name, &blk.range)?,
+                "size": number(&blk.name, &blk.width)?,
+                "regs": registers(&blk.name, &blk.register, attrs)?,
+                "rfs": rfs,
+            }))
+        })
+        .collect::<anyhow::Result<Vec<Value>, Error>>()?;
+
+    // the text form is the public way into the model, it is checked the same way
+    let compo = Component::deserialize(json!({
+        "vendor": xml.vendor,
+        "library": xml.library,
+        "name": xml.name,
+        "version": xml.version,
+        "blks": blks,
+    }))?;
+    compo.validate(attrs)?;
+    Ok(compo)
+}
+
+/// An IP-XACT number: decimal, `0x1F`, or SystemVerilog style such as `'h1F`,
+/// `8'b0001_1111` or `'d31`. `path` names the element in errors.
+fn number(path: &str, literal: &str) -> anyhow::Result<u64, Error> {
+    let s = literal.trim().replace('_', "");
+    let (radix, digits) = match s.split_once('\'') {
+        Some((_, rest)) => match rest.split_at_checked(1) {
+            Some(("h" | "H", digits)) => (16, digits),
+            Some(("d" | "D", digits)) => (10, digits),
+            Some(("o" | "O", digits)) => (8, digits),
+            Some(("b" | "B", digits)) => (2, digits),
+            _ => (10, ""),
+        },
+        None => match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(digits) => (16, digits),
+            None => (10, s.as_str()),
+        },
+    };
+    u64::from_str_radix(digits, radix)
+        .map_err(|_| Error::Input(format!("{}: `{}` is not a number", path, literal)))
+}
+
+/// The element count of an array, the model has one dimension so 2-D arrays
+/// are folded into it.
+fn dim(path: &str, dims: &[String]) -> anyhow::Result<u64, Error> {
+    let dim = dims.iter().try_fold(1u64, |dim, n| {
+        dim.checked_mul(number(path, n)?)
+            .ok_or_else(|| Error::Input(format!("{}: too many array elements", path)))
+    })?;
+    // register arrays become that many registers
+    if dim > MAX_DIM {
+        return Err(Error::Input(format!(
+            "{}: {} array elements, irgen reads at most {}",
+            path, dim, MAX_DIM
+        )));
+    }
+    Ok(dim)
+}
+
+fn registers(
+    parent: &str,
+    regs: &[XRegister],
+    attrs: &AttributeTable,
+) -> anyhow::Result<Vec<Value>, Error> {
+    let mut values = Vec::new();
+    for reg in regs {
+        let path = format!("{}.{}", parent, reg.name);
+        let fields = reg
+            .field
+            .iter()
+            .map(|field| {
+                let path = format!("{}.{}", path, field.name);
+                // fields without an access take the one of their register
+                let access = field
+                    .access
+                    .as_deref()
+                    .or(reg.access.as_deref())
+                    .unwrap_or("read-write");
+                let mwv = field.modified_write_value.as_deref();
+                let read_action = field.read_action.as_deref();
+                let attr = attrs.find(access, mwv, read_action).ok_or_else(|| {
+                    Error::Attribute(format!(
+                        "{}: no code for access {}, modifiedWriteValue {} and readAction {}, add one with --attributes",
+                        path,
+                        access,
+                        mwv.unwrap_or("-"),
+                        read_action.unwrap_or("-")
+                    ))
+                })?;
+
+                let resets = field
+                    .resets
+                    .iter()
+                    .flat_map(|resets| &resets.reset)
+                    .map(|reset| {
+                        let mask = reset.mask.as_deref().map(|mask| number(&path, mask));
+                        Ok(json!({
+                            "domain": reset.reset_type_ref,
+                            "value": number(&path, &reset.value)?,
+                            "mask": mask.transpose()?,
+                        }))
+                    })
+                    .collect::<anyhow::Result<Vec<Value>, Error>>()?;
+                // a field without a reset has an unknown value after it
+                let resets = if resets.is_empty() {
+                    tracing::warn!("{}: no reset, taken as unknown", path);
+                    vec![json!({"value": 0, "mask": 0})]
+                } else {
+                    resets
+                };
+                let enums = field
+                    .enumerated_values
+                    .iter()
+                    .flat_map(|values| &values.enumerated_value)
+                    .map(|value| {
+                        Ok(json!({
+                            "name": value.name,
+                            "value": number(&path, &value.value)?,
+                        }))
+                    })
+                    .collect::<anyhow::Result<Vec<Value>, Error>>()?;
+                Ok(json!({
+                    "name": field.name,
+                    "offset": number(&path, &field.bit_offset)?,
+                    "width": number(&path, &field.bit_width)?,
+                    "attr": attr,
+                    "volatile": field.volatile,
+                    "reserved": field.reserved.unwrap_or(false),
+                    "resets": resets,
+                    "enums": enums,
+                    "desc": field.description.as_deref().unwrap_or("No Description"),
+                }))
+            })
+            .collect::<anyhow::Result<Vec<Value>, Error>>()?;
+
+        // register arrays are packed, each element one register size after
+        // the other; the model has none, so they become single registers
+        let offset = number(&path, &reg.address_offset)?;
+        let size = number(&path, &reg.size)?;
+        let dim = dim(&path, &reg.dim)?;
+        for n in 0..dim {
+            let offset = n
+                .checked_mul(size)
+                .and_then(|bits| offset.checked_add(bits / 8))
+                .ok_or_else(|| Error::Input(format!("{}: array past the address space", path)))?;
+            values.push(json!({
+                "name": if reg.dim.is_empty() { reg.name.clone() } else { format!("{}_{}", reg.name, n) },
+                "offset": offset,
+                "size": size,
+                "fields": fields,
+            }));
+        }
+    }
+    Ok(values)
+}
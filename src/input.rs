@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use polars::prelude::*;
+
+use crate::args::ModelArgs;
+use crate::error::Error;
+use crate::parser::parse_register;
+use crate::schema::attr::AttributeTable;
+use crate::schema::base::{Component, df_to_blks, df_to_compo, df_to_regs, df_to_rfs};
+use crate::schema::reserved::Reserved;
+use crate::{csv, excel, soc, text};
+
+/// Builds the register map of `inputs` as every command sees it, with the
+/// reserved field policy applied.
+pub fn load(
+    inputs: &[String],
+    model: &ModelArgs,
+) -> anyhow::Result<(Component, AttributeTable), Error> {
+    let inputs: Vec<PathBuf> = inputs.iter().map(PathBuf::from).collect();
+    let attrs = AttributeTable::load(model.attributes.as_deref().map(Path::new))?;
+    let mut compo = read_input(&inputs, &attrs, &[])?;
+    compo.apply_reserved(&Reserved::new(
+        model.reserved.unwrap_or_default(),
+        &model.reserved_patterns,
+    )?);
+    Ok((compo, attrs))
+}
+
+/// The sheets of a workbook, a directory of CSV/TSV sheets or the sheets
+/// themselves, and the directory they are in.
+pub fn read_sheets(
+    inputs: &[PathBuf],
+) -> anyhow::Result<(HashMap<String, DataFrame>, PathBuf), Error> {
+    check_count(inputs)?;
+    let source = inputs[0].as_path();
+    if source.is_dir() {
+        return Ok((csv::read_dir(source)?, source.to_path_buf()));
+    }
+    let dir = source.parent().unwrap_or(source).to_path_buf();
+    if csv::is_csv(source) {
+        Ok((csv::read_files(inputs)?, dir))
+    } else {
+        Ok((excel::read_workbook(source)?, dir))
+    }
+}
+
+/// Only CSV/TSV sheets may be given one by one, a workbook, directory or
+/// text model is the whole input.
+fn check_count(inputs: &[PathBuf]) -> anyhow::Result<(), Error> {
+    match inputs
+        .iter()
+        .find(|input| !csv::is_csv(input) || input.is_dir())
+    {
+        Some(input) if inputs.len() > 1 => Err(Error::Input(format!(
+            "{} is read on its own, only CSV/TSV sheets can be given together",
+            input.display()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// The files `inputs` are read from, with the sources of a `soc` sheet.
+pub fn sources(inputs: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>, Error> {
+    sources_below(inputs, &[])
+}
+
+fn sources_below(inputs: &[PathBuf], parents: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>, Error> {
+    let mut sources = inputs.to_vec();
+    if text::is_model(&inputs[0]) {
+        return Ok(sources);
+    }
+    let parents = enter(&inputs[0], parents)?;
+    let (mut df_map, dir) = read_sheets(inputs)?;
+    if let Some(soc_df) = df_map.remove("soc") {
+        for instance in soc::instances(&soc_df, &dir)? {
+            sources.extend(sources_below(&[instance.source().to_path_buf()], &parents)?);
+        }
+    }
+    Ok(sources)
+}
+
+/// `parents` followed by `source`, the SoCs whose sources are being read.
+/// A source that is one of its own parents would be read forever.
+fn enter(source: &Path, parents: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>, Error> {
+    // a missing source is reported when it is read
+    let path = source
+        .canonicalize()
+        .unwrap_or_else(|_| source.to_path_buf());
+    if let Some(start) = parents.iter().position(|parent| *parent == path) {
+        let cycle: Vec<_> = parents[start..]
+            .iter()
+            .chain([&path])
+            .map(|path| path.display().to_string())
+            .collect();
+        return Err(Error::Input(format!(
+            "SoC sources form a cycle: {}",
+            cycle.join(" -> ")
+        )));
+    }
+    Ok([parents, &[path]].concat())
+}
+
+fn read_input(
+    inputs: &[PathBuf],
+    attrs: &AttributeTable,
+    parents: &[PathBuf],
+) -> anyhow::Result<Component, Error> {
+    let source = inputs[0].as_path();
+    if text::is_model(source) {
+        check_count(inputs)?;
+        let compo = text::read_model(source)?;
+        compo.validate(attrs)?;
+        return Ok(compo);
+    }
+
+    let parents = enter(source, parents)?;
+    let (df_map, dir) = read_sheets(inputs)?;
+    // the sheet parser misses what spans registers, like overlaps
+    let compo = sheets_to_compo(df_map, &dir, attrs, &parents)?;
+    compo.validate(attrs)?;
+    Ok(compo)
+}
+
+/// `dir` is where the sources of a `soc` sheet are looked up, `parents` the
+/// SoCs being read.
+fn sheets_to_compo(
+    mut df_map: HashMap<String, DataFrame>,
+    dir: &Path,
+    attrs: &AttributeTable,
+    parents: &[PathBuf],
+) -> anyhow::Result<Component, Error> {
+    let compo_df = df_map
+        .remove("version")
+        .ok_or_else(|| Error::NotFound("version".into()))?;
+
+    // a SoC lists IP instances instead of blocks
+    if let Some(soc_df) = df_map.remove("soc") {
+        return df_to_compo(compo_df, || {
+            let instances = soc::instances(&soc_df, dir)?;
+            soc::blocks(&instances, |source| {
+                read_input(&[source.into()], attrs, parents)
+            })
+        });
+    }
+
+    df_to_compo(compo_df, || {
+        let blks_df = df_map
+            .remove("address_map")
+            .ok_or_else(|| Error::NotFound("address_map".into()))?;
+
+        df_to_blks(blks_df, |s| {
+            tracing::debug!("block_name: {}", s);
+
+            let regs_df = df_map.remove(s).ok_or_else(|| Error::NotFound(s.into()))?;
+            let parsered_df = parse_register(regs_df)?;
+
+            Ok((df_to_regs(&parsered_df)?, df_to_rfs(&parsered_df)?))
+        })
+    })
+}
@@ -1,80 +1,105 @@
 mod args;
+mod changelog;
+mod commands;
+mod config;
+mod csv;
+mod diff;
 mod error;
 mod excel;
+#[cfg(test)]
+mod golden;
+mod header;
+mod import;
+mod input;
 mod logger;
+mod output;
 mod parser;
+mod python;
+mod rust;
 mod schema;
+mod soc;
+mod text;
+mod vhdl;
+mod watch;
 
-use std::collections::HashMap;
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-use calamine::{Reader, Xlsx, open_workbook};
-use clap::Parser;
-use polars::prelude::*;
+use clap::{CommandFactory, Parser};
 
-use crate::{
-    args::Args,
-    excel::ToDataFrame,
-    parser::parse_register,
-    schema::base::{df_to_blks, df_to_compo, df_to_regs},
-    schema::{ipxact, regvue},
-};
+use crate::args::{Args, Command, ConfigArgs};
+use crate::config::Config;
+use crate::error::Error;
 
-fn main() -> anyhow::Result<(), error::Error> {
+fn main() -> ExitCode {
     logger::init();
 
     let args = Args::parse();
-    let source = Path::new(&args.input);
-    let mut wb: Xlsx<_> = open_workbook(source)?;
-    let sheets = wb.worksheets();
-    let mut df_map: HashMap<String, DataFrame> = sheets
-        .iter()
-        .map(|(sheet_name, range_data)| {
-            range_data.to_data_frame().map(|df| (sheet_name.into(), df))
-        })
-        .collect::<Result<HashMap<_, _>, _>>()?;
-
-    let compo = {
-        let compo_df = df_map
-            .remove("version")
-            .ok_or_else(|| error::Error::NotFound("version".into()))?;
-
-        df_to_compo(compo_df, || {
-            let blks_df = df_map
-                .remove("address_map")
-                .ok_or_else(|| error::Error::NotFound("address_map".into()))?;
-
-            df_to_blks(blks_df, |s| {
-                tracing::debug!("block_name: {}", s);
-
-                let regs_df = df_map
-                    .remove(s)
-                    .ok_or_else(|| error::Error::NotFound(s.into()))?;
-                let parsered_df = parse_register(regs_df)?;
-
-                df_to_regs(parsered_df)
-            })
-        })?
+    let mut command = args.command.unwrap_or(Command::Generate(args.generate));
+    let result = match &command {
+        // reads irgen.toml again for every run
+        Command::Generate(args) if args.watch => watch::watch(args),
+        _ => load_config(command.config()).and_then(|config| {
+            match &mut command {
+                Command::Generate(args) => config.apply_generate(args),
+                Command::Check(args) => config.apply_input(args),
+                Command::Diff(args) => config.apply_model(&mut args.model),
+                Command::Changelog(args) => config.apply_model(&mut args.model),
+                Command::DumpModel(args) => config.apply_input(&mut args.input),
+                Command::Import(args) => config.apply_attributes(&mut args.attributes),
+                Command::ListSheets(args) if args.input.is_empty() => {
+                    args.input = config.input().to_vec()
+                }
+                _ => {}
+            }
+            run(command, &config)
+        }),
     };
 
-    let ipxact_compo = ipxact::Component::try_from(&compo)?;
-    let xml_str = quick_xml::se::to_string(&ipxact_compo)?;
-    let xml_file = args
-        .output
-        .as_deref()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| Path::new(&source).with_extension("xml"));
-
-    fs::write(&xml_file, xml_str)?;
+    match result {
+        Ok(code) => ExitCode::from(code),
+        Err(err) => {
+            tracing::error!("{}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
 
-    if args.regvue {
-        let regvue_doc = regvue::Document::try_from(&compo)?;
-        let json_str = serde_json::to_string_pretty(&regvue_doc)?;
-        let json_file = xml_file.with_extension("json");
+fn load_config(args: Option<&ConfigArgs>) -> anyhow::Result<Config, Error> {
+    match config::path(args)? {
+        Some(path) => {
+            tracing::debug!("config: {}", path.display());
+            Config::load(&path)
+        }
+        None => Ok(Config::default()),
+    }
+}
 
-        fs::write(&json_file, json_str)?;
+fn run(command: Command, config: &Config) -> anyhow::Result<u8, Error> {
+    let input = match &command {
+        Command::Generate(args) => Some(&args.input.input),
+        Command::Check(args) => Some(&args.input),
+        Command::DumpModel(args) => Some(&args.input.input),
+        Command::ListSheets(args) => Some(&args.input),
+        _ => None,
+    };
+    // required unless irgen.toml names it, so checked only now
+    if input.is_some_and(|input| input.is_empty()) {
+        Args::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "no --input given and no irgen.toml with `input` found",
+            )
+            .exit();
     }
 
-    Ok(())
+    match command {
+        Command::Generate(args) => commands::generate(&args),
+        Command::Check(args) => commands::check(&config.generate_args(&args)),
+        Command::Diff(args) => commands::diff(&args),
+        Command::Changelog(args) => commands::changelog(&args),
+        Command::Import(args) => commands::import(&args),
+        Command::ListSheets(args) => commands::list_sheets(&args),
+        Command::DumpModel(args) => commands::dump_model(&args),
+        Command::Formats => commands::formats(),
+    }
 }
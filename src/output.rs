@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::Error;
+
+/// Writes `contents` to a temporary file next to `path` and renames it over
+/// `path`, so that nobody reads a half-written output.
+pub fn write(path: &Path, contents: &str) -> anyhow::Result<(), Error> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| Error::Input(format!("{} is not a file", path.display())))?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    if let Err(err) = fs::write(&tmp, contents).and_then(|()| fs::rename(&tmp, path)) {
+        fs::remove_file(&tmp).ok();
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// Whether `path` holds exactly `contents`.
+pub fn is_current(path: &Path, contents: &str) -> bool {
+    fs::read(path).is_ok_and(|old| old == contents.as_bytes())
+}
+
+/// The current UTC time as `YYYY-MM-DDThh:mm:ssZ`, or the time in
+/// `SOURCE_DATE_EPOCH` for reproducible builds.
+pub fn timestamp() -> String {
+    let secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs())
+        });
+
+    // days since 1970-01-01 to a civil date, after Howard Hinnant
+    let (days, time) = (secs / 86400, secs % 86400);
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
@@ -0,0 +1,61 @@
+/// Parses a BIT cell into `(msb, lsb)`.
+///
+/// Accepts `[31:0]`, `31:0`, `[3]`, `3`, `bit 5`, `bits 7:0` and `31..16`, a
+/// reversed `[0:7]` is read as `[7:0]`.
+pub fn parse_bit(cell: &str) -> Option<(u64, u64)> {
+    let mut s = cell.trim();
+    let lower = s.to_ascii_lowercase();
+    if let Some(rest) = lower
+        .strip_prefix("bits")
+        .or_else(|| lower.strip_prefix("bit"))
+    {
+        s = s[s.len() - rest.len()..].trim_start();
+    }
+    if let Some(inner) = s.strip_prefix('[') {
+        s = inner.strip_suffix(']')?;
+    }
+
+    let (msb, lsb) = match s.split_once(':').or_else(|| s.split_once("..")) {
+        Some((msb, lsb)) => (msb.trim().parse().ok()?, lsb.trim().parse().ok()?),
+        None => {
+            let bit: u64 = s.trim().parse().ok()?;
+            (bit, bit)
+        }
+    };
+    Some((msb.max(lsb), msb.min(lsb)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges() {
+        assert_eq!(parse_bit("[31:0]"), Some((31, 0)));
+        assert_eq!(parse_bit(" 15:8 "), Some((15, 8)));
+        assert_eq!(parse_bit("bits 7:0"), Some((7, 0)));
+        assert_eq!(parse_bit("Bits [7 : 4]"), Some((7, 4)));
+        assert_eq!(parse_bit("31..16"), Some((31, 16)));
+    }
+
+    #[test]
+    fn single_bits() {
+        assert_eq!(parse_bit("[3]"), Some((3, 3)));
+        assert_eq!(parse_bit("3"), Some((3, 3)));
+        assert_eq!(parse_bit("bit 5"), Some((5, 5)));
+    }
+
+    #[test]
+    fn reversed_range_is_turned_around() {
+        assert_eq!(parse_bit("[0:7]"), Some((7, 0)));
+    }
+
+    #[test]
+    fn rejects_other_cells() {
+        assert_eq!(parse_bit(""), None);
+        assert_eq!(parse_bit("[7:0"), None);
+        assert_eq!(parse_bit("7-0"), None);
+        assert_eq!(parse_bit("a:b"), None);
+        assert_eq!(parse_bit("bit"), None);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::parser::parse_number;
+
+/// Parses an ENUM cell into `(name, value)` pairs.
+///
+/// Values are separated by `,`, `;` or line breaks and written as `IDLE=0`,
+/// `RUN = 0x1` or `STOP=2'b10`. Names have to be identifiers, and names and
+/// values must be unique and fit in a field `width` bits wide.
+pub fn parse_enums(cell: &str, width: u64) -> Result<Vec<(String, u64)>, String> {
+    let mut values = Vec::new();
+    for item in cell.split([',', ';', '\n']) {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+        let Some((name, value)) = item.split_once('=') else {
+            return Err(format!("`{}` is not NAME=VALUE", item));
+        };
+        let value = parse_number(value.trim())
+            .ok_or_else(|| format!("`{}` is not a valid value", value.trim()))?;
+        values.push((name.trim().to_owned(), value));
+    }
+    check_enums(
+        values.iter().map(|(name, value)| (name.as_str(), *value)),
+        width,
+    )?;
+    Ok(values)
+}
+
+/// Whether the names are unique identifiers, even without regard to case, and
+/// the values unique numbers that fit in `width` bits.
+pub fn check_enums<'a>(
+    values: impl IntoIterator<Item = (&'a str, u64)>,
+    width: u64,
+) -> Result<(), String> {
+    let mut seen: Vec<(String, u64)> = Vec::new();
+    for (name, value) in values {
+        let ident = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !ident {
+            return Err(format!("`{}` is not a valid enum name", name));
+        }
+        if width < 64 && value >> width != 0 {
+            return Err(format!(
+                "{} = 0x{:X} does not fit in {} bits",
+                name, value, width
+            ));
+        }
+        let key = name.to_ascii_lowercase();
+        if let Some((other, _)) = seen
+            .iter()
+            .find(|(other, _)| other.to_ascii_lowercase() == key)
+        {
+            return Err(format!("`{}` is given twice, also as `{}`", name, other));
+        }
+        if let Some((other, _)) = seen.iter().find(|(_, known)| *known == value) {
+            return Err(format!("{} and {} are both 0x{:X}", other, name, value));
+        }
+        seen.push((name.to_owned(), value));
+    }
+    Ok(())
+}
@@ -0,0 +1,503 @@
+//! Grammar of the `{n}` expansion used by REG, GROUP and FIELD cells.
+//!
+//! ```text
+//! spec     = template { "," binding }
+//! template = { text | "{" ident [ ":" width ] "}" }
+//! binding  = ident "=" item { "," item }
+//!          | "stride" [ "_" ident ] "=" number
+//! item     = number [ "~" number ]
+//! number   = decimal | "0x" hex
+//! ```
+//!
+//! `ch{i}_lane{j:02}, i=0~3, j=0,2,5, stride_i=0x100` expands to `ch_0_lane_00`,
+//! `ch_0_lane_02`, ... A placeholder directly after a letter or digit is joined
+//! with `_`, so `reg{n}` gives `reg_0`. The last index advances by the element
+//! size (bytes for registers, bits for fields) unless a stride is given, outer
+//! indices advance by the span of the inner ones.
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Index { name: String, width: usize },
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+struct Index {
+    name: String,
+    values: Vec<u64>,
+    stride: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    template: Template,
+    indices: Vec<Index>,
+}
+
+/// One element of an expanded pattern.
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub name: String,
+    pub offset: u64,
+    pub indices: Vec<(String, u64)>,
+}
+
+fn err(spec: &str, reason: impl Into<String>) -> Error {
+    Error::Expand(spec.into(), reason.into())
+}
+
+fn parse_number(spec: &str, s: &str) -> anyhow::Result<u64, Error> {
+    let s = s.trim();
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => s.parse::<u64>(),
+    };
+    parsed.map_err(|_| err(spec, format!("`{}` is not a number", s)))
+}
+
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl Template {
+    pub fn parse(spec: &str) -> anyhow::Result<Self, Error> {
+        let mut segments = Vec::new();
+        let mut rest = spec.trim();
+        while let Some(open) = rest.find('{') {
+            if open > 0 {
+                segments.push(Segment::Text(rest[..open].into()));
+            }
+            let close = rest[open..]
+                .find('}')
+                .ok_or_else(|| err(spec, "unclosed `{`"))?
+                + open;
+            let inner = &rest[open + 1..close];
+            let (name, width) = match inner.split_once(':') {
+                Some((name, width)) => (
+                    name.trim(),
+                    width
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| err(spec, format!("bad width in `{{{}}}`", inner)))?,
+                ),
+                None => (inner.trim(), 0),
+            };
+            if !is_ident(name) {
+                return Err(err(spec, format!("bad index name `{{{}}}`", inner)));
+            }
+            segments.push(Segment::Index {
+                name: name.into(),
+                width,
+            });
+            rest = &rest[close + 1..];
+        }
+        if rest.contains('}') {
+            return Err(err(spec, "unmatched `}`"));
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.into()));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Index names in order of first appearance.
+    fn index_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for segment in &self.segments {
+            if let Segment::Index { name, .. } = segment
+                && !names.contains(&name.as_str())
+            {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// The template with its placeholders left out, `ch{n}` gives `ch`.
+    pub fn key(&self) -> String {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Text(text) => Some(text.as_str()),
+                Segment::Index { .. } => None,
+            })
+            .collect()
+    }
+
+    pub fn render(&self, indices: &[(String, u64)]) -> anyhow::Result<String, Error> {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Index { name, width } => {
+                    let value = indices
+                        .iter()
+                        .find(|(index, _)| index == name)
+                        .map(|(_, value)| *value)
+                        .ok_or_else(|| {
+                            err(&self.to_string(), format!("index `{}` has no range", name))
+                        })?;
+                    if out.ends_with(|c: char| c.is_ascii_alphanumeric()) {
+                        out.push('_');
+                    }
+                    out.push_str(&format!("{:0width$}", value, width = *width));
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl std::fmt::Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => write!(f, "{}", text)?,
+                Segment::Index { name, width: 0 } => write!(f, "{{{}}}", name)?,
+                Segment::Index { name, width } => write!(f, "{{{}:{}}}", name, width)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Pattern {
+    pub fn parse(spec: &str) -> anyhow::Result<Self, Error> {
+        Self::parse_with(spec, &[])
+    }
+
+    /// Parses a pattern whose placeholders may also refer to the `outer`
+    /// indices, e.g. a field named after the index of its register.
+    pub fn parse_with(spec: &str, outer: &[&str]) -> anyhow::Result<Self, Error> {
+        let mut parts = spec.split(',');
+        let template = Template::parse(parts.next().unwrap_or_default())?;
+
+        let mut indices: Vec<Index> = Vec::new();
+        let mut strides: Vec<(Option<String>, u64)> = Vec::new();
+        for part in parts {
+            let part = part.trim();
+            let Some((key, value)) = part.split_once('=') else {
+                // continuation of a list, "n=0,2,5"
+                let index = indices
+                    .last_mut()
+                    .ok_or_else(|| err(spec, format!("`{}` is not a binding", part)))?;
+                push_item(spec, &mut index.values, part)?;
+                continue;
+            };
+            let key = key.trim();
+            if key == "stride" {
+                strides.push((None, parse_number(spec, value)?));
+            } else if let Some(name) = key.strip_prefix("stride_") {
+                strides.push((Some(name.into()), parse_number(spec, value)?));
+            } else if !is_ident(key) {
+                return Err(err(spec, format!("bad index name `{}`", key)));
+            } else if outer.contains(&key) {
+                return Err(err(
+                    spec,
+                    format!("index `{}` is already bound outside", key),
+                ));
+            } else if indices.iter().any(|index| index.name == key) {
+                return Err(err(spec, format!("index `{}` is bound twice", key)));
+            } else {
+                let mut values = Vec::new();
+                push_item(spec, &mut values, value)?;
+                indices.push(Index {
+                    name: key.into(),
+                    values,
+                    stride: None,
+                });
+            }
+        }
+
+        for index in &indices {
+            let mut values = index.values.clone();
+            values.sort_unstable();
+            if values.windows(2).any(|pair| pair[0] == pair[1]) {
+                return Err(err(spec, format!("index `{}` repeats a value", index.name)));
+            }
+        }
+
+        // order indices like the template, outermost first
+        let names = template.index_names();
+        for index in &indices {
+            if !names.contains(&index.name.as_str()) {
+                return Err(err(spec, format!("index `{}` is not used", index.name)));
+            }
+        }
+        let mut ordered = Vec::new();
+        for name in names {
+            if outer.contains(&name) {
+                continue;
+            }
+            let position = indices
+                .iter()
+                .position(|index| index.name == name)
+                .ok_or_else(|| err(spec, format!("index `{}` has no range", name)))?;
+            ordered.push(indices.remove(position));
+        }
+
+        for (name, stride) in strides {
+            let index = match name {
+                Some(name) => ordered
+                    .iter_mut()
+                    .find(|index| index.name == name)
+                    .ok_or_else(|| err(spec, format!("stride for unknown index `{}`", name)))?,
+                None if ordered.len() == 1 => &mut ordered[0],
+                None => {
+                    return Err(err(
+                        spec,
+                        "`stride` is ambiguous with several indices, use `stride_<index>`",
+                    ));
+                }
+            };
+            if index.stride.replace(stride).is_some() {
+                return Err(err(spec, format!("stride of `{}` given twice", index.name)));
+            }
+        }
+
+        Ok(Self {
+            template,
+            indices: ordered,
+        })
+    }
+
+    /// Name of the pattern without placeholders, the bindings are not checked.
+    pub fn key(spec: &str) -> anyhow::Result<String, Error> {
+        Ok(Template::parse(spec.split(',').next().unwrap_or_default())?.key())
+    }
+
+    pub fn is_expandable(&self) -> bool {
+        !self.indices.is_empty()
+    }
+
+    /// Number of elements the pattern expands to.
+    pub fn count(&self) -> usize {
+        self.indices
+            .iter()
+            .map(|index| index.values.len())
+            .product()
+    }
+
+    pub fn index_names(&self) -> Vec<&str> {
+        self.indices
+            .iter()
+            .map(|index| index.name.as_str())
+            .collect()
+    }
+
+    /// `Some((name, dim, stride))` if the pattern is a plain `name{n}, n=0~N`
+    /// array whose elements are named `name_0` .. `name_N`.
+    pub fn as_array(
+        &self,
+        outer: &[(String, u64)],
+    ) -> anyhow::Result<Option<(String, u64, Option<u64>)>, Error> {
+        let [index] = self.indices.as_slice() else {
+            return Ok(None);
+        };
+        let Some((Segment::Index { name, width: 0 }, head)) = self.template.segments.split_last()
+        else {
+            return Ok(None);
+        };
+        let is_contiguous = index
+            .values
+            .iter()
+            .enumerate()
+            .all(|(i, value)| i as u64 == *value);
+        let is_repeated = head
+            .iter()
+            .any(|segment| matches!(segment, Segment::Index { name, .. } if *name == index.name));
+        if *name != index.name || !is_contiguous || is_repeated {
+            return Ok(None);
+        }
+
+        let head = Template {
+            segments: head.to_vec(),
+        }
+        .render(outer)?;
+        if !head.ends_with(|c: char| c.is_ascii_alphanumeric()) {
+            return Ok(None);
+        }
+        Ok(Some((head, index.values.len() as u64, index.stride)))
+    }
+
+    /// Expands the pattern, `size` is the default stride of the last index.
+    pub fn instances(
+        &self,
+        size: u64,
+        outer: &[(String, u64)],
+    ) -> anyhow::Result<Vec<Instance>, Error> {
+        let mut strides = vec![0; self.indices.len()];
+        let mut inner = size;
+        for (k, index) in self.indices.iter().enumerate().rev() {
+            strides[k] = index.stride.unwrap_or(inner);
+            inner = strides[k] * (index.values.iter().max().copied().unwrap_or_default() + 1);
+        }
+
+        let mut instances = vec![Instance {
+            name: String::new(),
+            offset: 0,
+            indices: outer.to_vec(),
+        }];
+        for (index, stride) in self.indices.iter().zip(strides) {
+            instances = instances
+                .into_iter()
+                .flat_map(|instance| {
+                    index.values.iter().map(move |value| {
+                        let mut indices = instance.indices.clone();
+                        indices.push((index.name.clone(), *value));
+                        Instance {
+                            name: String::new(),
+                            offset: instance.offset + value * stride,
+                            indices,
+                        }
+                    })
+                })
+                .collect();
+        }
+
+        for instance in &mut instances {
+            instance.name = self.template.render(&instance.indices)?;
+        }
+        Ok(instances)
+    }
+}
+
+fn push_item(spec: &str, values: &mut Vec<u64>, item: &str) -> anyhow::Result<(), Error> {
+    match item.split_once('~') {
+        Some((start, end)) => {
+            let (start, end) = (parse_number(spec, start)?, parse_number(spec, end)?);
+            if start > end {
+                return Err(err(spec, format!("empty range `{}`", item.trim())));
+            }
+            values.extend(start..=end);
+        }
+        None => values.push(parse_number(spec, item)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names_and_offsets(spec: &str, size: u64) -> Vec<(String, u64)> {
+        Pattern::parse(spec)
+            .unwrap()
+            .instances(size, &[])
+            .unwrap()
+            .into_iter()
+            .map(|instance| (instance.name, instance.offset))
+            .collect()
+    }
+
+    fn pairs(items: &[(&str, u64)]) -> Vec<(String, u64)> {
+        items
+            .iter()
+            .map(|(name, offset)| (name.to_string(), *offset))
+            .collect()
+    }
+
+    #[test]
+    fn plain_name_is_a_single_instance() {
+        let pattern = Pattern::parse("ctrl").unwrap();
+        assert!(!pattern.is_expandable());
+        assert_eq!(pattern.count(), 1);
+        assert_eq!(names_and_offsets("ctrl", 4), pairs(&[("ctrl", 0)]));
+    }
+
+    #[test]
+    fn range_advances_by_the_size() {
+        assert_eq!(
+            names_and_offsets("reg{n}, n=0~2", 4),
+            pairs(&[("reg_0", 0), ("reg_1", 4), ("reg_2", 8)])
+        );
+    }
+
+    #[test]
+    fn lists_widths_and_strides() {
+        assert_eq!(
+            names_and_offsets("ch{i}_lane{j:02}, i=0~1, j=0,2, stride_i=0x100", 4),
+            pairs(&[
+                ("ch_0_lane_00", 0x0),
+                ("ch_0_lane_02", 0x8),
+                ("ch_1_lane_00", 0x100),
+                ("ch_1_lane_02", 0x108),
+            ])
+        );
+    }
+
+    #[test]
+    fn outer_index_spans_the_inner_ones() {
+        // j reaches 2, so i advances by 3 elements
+        assert_eq!(
+            names_and_offsets("r{i}_{j}, i=0~1, j=0,2", 4),
+            pairs(&[("r_0_0", 0), ("r_0_2", 8), ("r_1_0", 12), ("r_1_2", 20)])
+        );
+    }
+
+    #[test]
+    fn field_may_use_the_index_of_its_register() {
+        let reg = Pattern::parse("ch{n}, n=0~1").unwrap();
+        let field = Pattern::parse_with("en{n}", &reg.index_names()).unwrap();
+        let names: Vec<String> = reg
+            .instances(4, &[])
+            .unwrap()
+            .iter()
+            .map(|instance| {
+                field
+                    .instances(1, &instance.indices)
+                    .unwrap()
+                    .remove(0)
+                    .name
+            })
+            .collect();
+        assert_eq!(names, ["en_0", "en_1"]);
+    }
+
+    #[test]
+    fn plain_array_keeps_its_dim() {
+        let pattern = Pattern::parse("prio{n}, n=0~7, stride=4").unwrap();
+        assert_eq!(
+            pattern.as_array(&[]).unwrap(),
+            Some(("prio".to_string(), 8, Some(4)))
+        );
+        // other ranges are flattened
+        let pattern = Pattern::parse("prio{n}, n=1~7").unwrap();
+        assert_eq!(pattern.as_array(&[]).unwrap(), None);
+    }
+
+    #[test]
+    fn key_leaves_out_the_placeholders() {
+        assert_eq!(Pattern::key("ch{n}, n=0~15").unwrap(), "ch");
+    }
+
+    #[test]
+    fn rejects_bad_specs() {
+        for spec in [
+            "reg{n",
+            "reg{n}}",
+            "reg{n}",
+            "reg{n}, n=3~1",
+            "reg{n}, n=0,0",
+            "reg{n}, m=0~1",
+            "reg{n}, n=0~1, n=2",
+            "reg{n}, n=a",
+            "r{i}_{j}, i=0~1, j=0~1, stride=4",
+            "reg{n}, n=0~1, stride=4, stride=8",
+        ] {
+            assert!(Pattern::parse(spec).is_err(), "{}", spec);
+        }
+        assert!(Pattern::parse_with("en{n}, n=0~1", &["n"]).is_err());
+    }
+}
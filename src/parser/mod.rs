@@ -0,0 +1,322 @@
+pub mod bit;
+pub mod enums;
+pub mod expand;
+pub mod reset;
+
+use std::collections::HashMap;
+
+use crate::error::Error;
+use crate::parser::bit::parse_bit;
+use crate::parser::expand::Pattern;
+use crate::schema::DEFAULT_RESET;
+use crate::schema::base::parse_hex;
+use polars::prelude::*;
+
+/// Prefix of the DEFAULT columns of named reset domains.
+pub const RESET_PREFIX: &str = "DEFAULT:";
+
+pub fn parse_register(df: DataFrame) -> anyhow::Result<DataFrame, Error> {
+    // GROUP, WIDTH, HW_ACCESS, VOLATILE and ENUM are optional, WIDTH can be
+    // derived from BIT
+    let mut lf = df.lazy();
+    for name in ["GROUP", "WIDTH", "HW_ACCESS", "VOLATILE", "ENUM"] {
+        if lf.collect_schema()?.get(name).is_none() {
+            lf = lf.with_column(lit(NULL).cast(DataType::String).alias(name));
+        }
+    }
+
+    // DEFAULT:warm, DEFAULT:debug, ... give the values of further reset domains
+    let reset_cols: Vec<String> = lf
+        .collect_schema()?
+        .iter_names()
+        .filter(|name| name.starts_with(RESET_PREFIX))
+        .map(|name| name.to_string())
+        .collect();
+    for name in &reset_cols {
+        let domain = name[RESET_PREFIX.len()..].trim();
+        if domain.is_empty() || domain.eq_ignore_ascii_case(DEFAULT_RESET) {
+            return Err(Error::Cell(format!(
+                "`{}` needs a reset domain other than {}",
+                name, DEFAULT_RESET
+            )));
+        }
+    }
+
+    let filled_df = lf
+        // fullfill empty description
+        .with_column(
+            when(col("DESCRIPTION").is_null())
+                .then(lit("No Description"))
+                .otherwise(col("DESCRIPTION"))
+                .alias("DESCRIPTION"),
+        )
+        // Unmerge cells and distribute content to each cell
+        // GROUP is only written on a register's first row, so it must not leak into
+        // the registers that follow a group, the others belong to a single field
+        .select(
+            [
+                all()
+                    .exclude_cols(["GROUP", "BIT", "WIDTH", "HW_ACCESS", "VOLATILE", "ENUM"])
+                    .exclude_cols(&reset_cols)
+                    .as_expr()
+                    .fill_null_with_strategy(FillNullStrategy::Forward(None)),
+                col("GROUP"),
+                col("BIT"),
+                col("WIDTH"),
+                col("HW_ACCESS"),
+                col("VOLATILE"),
+                col("ENUM"),
+            ]
+            .into_iter()
+            .chain(reset_cols.iter().map(|name| col(name.as_str())))
+            .collect::<Vec<_>>(),
+        )
+        .with_columns(&[
+            // registers are named on their first row only
+            col("REG").first().over(&[col("ADDR")]).alias("REG"),
+            // register file the register belongs to, "ch{n}, n=0~15"
+            col("GROUP").first().over(&[col("ADDR")]).alias("GROUP"),
+        ])
+        .filter(col("FIELD").is_not_null().and(col("FIELD").neq(lit(""))))
+        .collect()?;
+
+    let parsed_df = expand(filled_df)?
+        .lazy()
+        // registers of different register files may share a name
+        .group_by_stable(["GROUP_NAME", "REG"])
+        .agg(
+            [
+                col("ADDR").first(),
+                col("REG_WIDTH").first(),
+                col("FIELD"),
+                // col("BIT"),
+                col("WIDTH"),
+                col("FIELD_DIM"),
+                col("FIELD_STRIDE"),
+                col("ATTRIBUTE"),
+                col("HW_ACCESS"),
+                col("VOLATILE"),
+                col("ENUM"),
+                // col("BYTES"),
+                col("BIT_OFFSET"),
+                col("DEFAULT"),
+                col("DESCRIPTION"),
+                col("GROUP").first(),
+            ]
+            .into_iter()
+            .chain(reset_cols.iter().map(|name| col(name.as_str())))
+            .collect::<Vec<_>>(),
+        )
+        .collect()?;
+
+    Ok(parsed_df)
+}
+
+/// Explodes "reg{n}, n=0~3" into one set of rows per register and
+/// "prio{n}, n=0~7" into one row per field, and resolves the register file of
+/// every row.
+fn expand(df: DataFrame) -> anyhow::Result<DataFrame, Error> {
+    let regs = df.column("REG")?.str()?;
+    let addrs = df.column("ADDR")?.str()?;
+    let fields = df.column("FIELD")?.str()?;
+    let bits = df.column("BIT")?.str()?;
+    let widths = df.column("WIDTH")?.str()?;
+    let hw_accesses = df.column("HW_ACCESS")?.str()?;
+    let volatiles = df.column("VOLATILE")?.str()?;
+    let groups = df.column("GROUP")?.str()?;
+
+    // the ranges of a register file may be written on any one of its registers
+    let mut group_specs: HashMap<String, &str> = HashMap::new();
+    for spec in groups.into_iter().flatten() {
+        let key = Pattern::key(spec)?;
+        match group_specs.get(&key) {
+            Some(known) if known.contains(',') && spec.contains(',') && *known != spec => {
+                return Err(Error::Expand(
+                    spec.into(),
+                    format!("register file `{}` is also given as `{}`", key, known),
+                ));
+            }
+            Some(known) if known.contains(',') => {}
+            _ => {
+                group_specs.insert(key, spec);
+            }
+        }
+    }
+
+    let no_data = || PolarsError::NoData("No data in DataFrame".into());
+
+    // caculate reg width by sum field width, field arrays count once per element
+    let mut rows = Vec::with_capacity(df.height());
+    let mut reg_widths: HashMap<&str, u64> = HashMap::new();
+    let mut cell_errors = Vec::new();
+    for i in 0..df.height() {
+        let addr = addrs.get(i).ok_or_else(no_data)?;
+        let reg_spec = regs.get(i).ok_or_else(no_data)?;
+        let field_spec = fields.get(i).ok_or_else(no_data)?;
+        let reg = Pattern::parse(reg_spec)?;
+        let field = Pattern::parse_with(field_spec, &reg.index_names())?;
+
+        // BIT gives the offset and the width, WIDTH is only a cross-check
+        let bit = bits.get(i).unwrap_or_default();
+        let Some((msb, lsb)) = parse_bit(bit) else {
+            cell_errors.push(format!(
+                "{}.{}: can't parse BIT `{}`",
+                reg_spec, field_spec, bit
+            ));
+            continue;
+        };
+        let width = msb - lsb + 1;
+        if let Some(cell) = widths.get(i)
+            && cell.trim().parse::<u64>().ok() != Some(width)
+        {
+            cell_errors.push(format!(
+                "{}.{}: BIT `{}` is {} bits wide but WIDTH is `{}`",
+                reg_spec, field_spec, bit, width, cell
+            ));
+            continue;
+        }
+
+        let hw_access = hw_accesses.get(i).map(|cell| {
+            parse_hw_access(cell)
+                .ok_or_else(|| format!("{}.{}: bad HW_ACCESS `{}`", reg_spec, field_spec, cell))
+        });
+        let volatile = volatiles.get(i).map(|cell| {
+            parse_volatile(cell)
+                .ok_or_else(|| format!("{}.{}: bad VOLATILE `{}`", reg_spec, field_spec, cell))
+        });
+        let (hw_access, volatile) = match (hw_access.transpose(), volatile.transpose()) {
+            (Ok(hw_access), Ok(volatile)) => (hw_access, volatile),
+            (hw_access, volatile) => {
+                cell_errors.extend(hw_access.err());
+                cell_errors.extend(volatile.err());
+                continue;
+            }
+        };
+
+        *reg_widths.entry(addr).or_default() += width * field.count() as u64;
+        rows.push((addr, reg, field, lsb, width, hw_access, volatile));
+    }
+    if !cell_errors.is_empty() {
+        for message in &cell_errors {
+            tracing::error!("{}", message);
+        }
+        return Err(Error::Cell(cell_errors.join("; ")));
+    }
+
+    let mut take: Vec<IdxSize> = Vec::new();
+    let mut reg_col = Vec::new();
+    let mut addr_col = Vec::new();
+    let mut reg_width_col = Vec::new();
+    let mut field_col = Vec::new();
+    let mut width_col = Vec::new();
+    let mut bit_offset_col = Vec::new();
+    let mut field_dim_col = Vec::new();
+    let mut field_stride_col = Vec::new();
+    let mut hw_access_col = Vec::new();
+    let mut volatile_col = Vec::new();
+    let mut group_name_col = Vec::new();
+    let mut group_col = Vec::new();
+
+    for (i, (addr, reg, field, bit_offset, width, hw_access, volatile)) in
+        rows.into_iter().enumerate()
+    {
+        let reg_width = reg_widths[addr];
+        let group = groups.get(i).map(Pattern::key).transpose()?;
+
+        let reg_instances = if reg.is_expandable() {
+            let base = parse_hex(addr)?;
+            reg.instances(reg_width / 8, &[])?
+                .into_iter()
+                .map(|instance| (format!("0x{:X}", base + instance.offset), instance))
+                .collect()
+        } else {
+            vec![(addr.to_owned(), reg.instances(0, &[])?.remove(0))]
+        };
+
+        for (addr, reg_instance) in reg_instances {
+            // plain arrays stay a single field with a dim, others are flattened
+            let field_instances = match field.as_array(&reg_instance.indices)? {
+                Some((name, dim, stride)) => {
+                    vec![(name, bit_offset, dim, stride.unwrap_or(width))]
+                }
+                None => field
+                    .instances(width, &reg_instance.indices)?
+                    .into_iter()
+                    .map(|instance| (instance.name, bit_offset + instance.offset, 1, width))
+                    .collect(),
+            };
+
+            for (name, bit_offset, dim, stride) in field_instances {
+                take.push(i as IdxSize);
+                reg_col.push(reg_instance.name.clone());
+                addr_col.push(addr.clone());
+                reg_width_col.push(reg_width.to_string());
+                field_col.push(name);
+                width_col.push(width.to_string());
+                bit_offset_col.push(bit_offset.to_string());
+                field_dim_col.push(dim.to_string());
+                field_stride_col.push(stride.to_string());
+                hw_access_col.push(hw_access);
+                volatile_col.push(volatile.map(|volatile| volatile.to_string()));
+                group_col.push(group.as_ref().map(|key| group_specs[key]));
+                group_name_col.push(group.clone());
+            }
+        }
+    }
+
+    let mut df = df.take(&IdxCa::from_vec("take".into(), take))?;
+    df.with_column(Column::new("REG".into(), reg_col))?;
+    df.with_column(Column::new("ADDR".into(), addr_col))?;
+    df.with_column(Column::new("REG_WIDTH".into(), reg_width_col))?;
+    df.with_column(Column::new("FIELD".into(), field_col))?;
+    df.with_column(Column::new("WIDTH".into(), width_col))?;
+    df.with_column(Column::new("BIT_OFFSET".into(), bit_offset_col))?;
+    df.with_column(Column::new("FIELD_DIM".into(), field_dim_col))?;
+    df.with_column(Column::new("FIELD_STRIDE".into(), field_stride_col))?;
+    df.with_column(Column::new("HW_ACCESS".into(), hw_access_col))?;
+    df.with_column(Column::new("VOLATILE".into(), volatile_col))?;
+    df.with_column(Column::new("GROUP".into(), group_col))?;
+    df.with_column(Column::new("GROUP_NAME".into(), group_name_col))?;
+
+    Ok(df)
+}
+
+/// What hardware does with a field: `r`, `w`, `rw` or `na`.
+fn parse_hw_access(cell: &str) -> Option<&'static str> {
+    match cell.trim().to_ascii_uppercase().as_str() {
+        "R" | "RO" => Some("r"),
+        "W" | "WO" => Some("w"),
+        "RW" | "WR" => Some("rw"),
+        "NA" | "-" | "NONE" => Some("na"),
+        _ => None,
+    }
+}
+
+fn parse_volatile(cell: &str) -> Option<bool> {
+    match cell.trim().to_ascii_uppercase().as_str() {
+        "TRUE" | "YES" | "Y" | "1" => Some(true),
+        "FALSE" | "NO" | "N" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// A number written as `12`, `0xC`, `0b1100` or in Verilog as `4'hC`,
+/// `'d12`, ...
+pub fn parse_number(s: &str) -> Option<u64> {
+    let s = s.trim().to_ascii_lowercase().replace('_', "");
+    let (radix, digits) = match s.split_once('\'') {
+        Some((_, rest)) => match rest.split_at_checked(1)? {
+            ("b", digits) => (2, digits),
+            ("o", digits) => (8, digits),
+            ("d", digits) => (10, digits),
+            ("h", digits) => (16, digits),
+            _ => return None,
+        },
+        None => match (s.strip_prefix("0b"), s.strip_prefix("0x")) {
+            (Some(digits), _) => (2, digits),
+            (_, Some(digits)) => (16, digits),
+            _ => (10, s.as_str()),
+        },
+    };
+    u64::from_str_radix(digits, radix).ok()
+}
@@ -0,0 +1,99 @@
+const UNKNOWN: [char; 3] = ['x', '?', '-'];
+
+/// Splits a DEFAULT cell with unknown bits into `(value, mask)`, where the set
+/// mask bits are the bits with a known reset value.
+///
+/// `x`, `?` or `-` alone leave the whole field unknown. In `0b10x1`, `0xF?`,
+/// `4'b10x1` or `8'hx0` they stand for single bits or hex digits. Values
+/// without unknown bits give `None` and are kept as written.
+pub fn parse_reset(cell: &str, width: u64) -> Result<Option<(u64, u64)>, String> {
+    let s = cell.trim().to_ascii_lowercase();
+    if !s.is_empty() && s.chars().all(|c| UNKNOWN.contains(&c)) {
+        return Ok(Some((0, 0)));
+    }
+
+    let (bits, digits) = match s.split_once('\'') {
+        Some((_, rest)) => match rest.split_at_checked(1) {
+            Some(("b", digits)) => (1, digits),
+            Some(("h", digits)) => (4, digits),
+            _ => return Ok(None),
+        },
+        None => match (s.strip_prefix("0b"), s.strip_prefix("0x")) {
+            (Some(digits), _) => (1, digits),
+            (_, Some(digits)) => (4, digits),
+            _ => return Ok(None),
+        },
+    };
+    if !digits.contains(UNKNOWN) {
+        return Ok(None);
+    }
+
+    let all = if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    };
+    let digit_mask = (1 << bits) - 1;
+    let (mut value, mut mask) = (0u64, 0u64);
+    for (n, c) in digits.chars().filter(|&c| c != '_').enumerate() {
+        if (n as u32 + 1) * bits > u64::BITS {
+            return Err(format!("`{}` does not fit in {} bits", cell, width));
+        }
+        value <<= bits;
+        mask <<= bits;
+        if UNKNOWN.contains(&c) {
+            continue;
+        }
+        let digit = c
+            .to_digit(1 << bits)
+            .ok_or_else(|| format!("`{}` is not a valid reset value", cell))?;
+        value |= u64::from(digit);
+        mask |= digit_mask;
+    }
+    if value & !all != 0 {
+        return Err(format!("`{}` does not fit in {} bits", cell, width));
+    }
+    Ok(Some((value, mask & all)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_field() {
+        assert_eq!(parse_reset("x", 8), Ok(Some((0, 0))));
+        assert_eq!(parse_reset("?", 8), Ok(Some((0, 0))));
+        assert_eq!(parse_reset(" -- ", 8), Ok(Some((0, 0))));
+    }
+
+    #[test]
+    fn unknown_bits_and_digits() {
+        assert_eq!(parse_reset("0b10x1", 4), Ok(Some((0b1001, 0b1101))));
+        assert_eq!(parse_reset("4'b10?1", 4), Ok(Some((0b1001, 0b1101))));
+        assert_eq!(parse_reset("0xF?", 8), Ok(Some((0xF0, 0xF0))));
+        assert_eq!(parse_reset("8'hX0", 8), Ok(Some((0x00, 0x0F))));
+        assert_eq!(parse_reset("0b1_x", 2), Ok(Some((0b10, 0b10))));
+    }
+
+    #[test]
+    fn mask_is_cut_to_the_width() {
+        // the unknown digit reaches past the 6 bits
+        assert_eq!(parse_reset("0x?1", 6), Ok(Some((0x1, 0xF))));
+    }
+
+    #[test]
+    fn known_values_are_kept_as_written() {
+        assert_eq!(parse_reset("0x12", 8), Ok(None));
+        assert_eq!(parse_reset("8'h12", 8), Ok(None));
+        assert_eq!(parse_reset("7", 8), Ok(None));
+        assert_eq!(parse_reset("", 8), Ok(None));
+    }
+
+    #[test]
+    fn rejects_values_that_do_not_fit() {
+        assert!(parse_reset("0x1?", 4).is_err());
+        assert!(parse_reset("0b12x", 4).is_err());
+        assert!(parse_reset("0x?0000000000000000", 64).is_err());
+    }
+}
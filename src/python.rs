@@ -0,0 +1,510 @@
+use crate::error::Error;
+use crate::schema::attr::AttributeTable;
+use crate::schema::base::{Component, Register, parse_hex};
+
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+// taken by the classes below
+const MEMBERS: [&str; 2] = ["base", "registers"];
+
+// in the module already, a block of the same name gets `Block` and a
+// component a `_`
+const CLASSES: [&str; 7] = [
+    "AccessError",
+    "Backend",
+    "CallbackBackend",
+    "MemoryBackend",
+    "Field",
+    "Register",
+    "Group",
+];
+
+/// What every generated module starts with: the backend, field and register
+/// classes the blocks are made of.
+const RUNTIME: &str = r#"import inspect
+
+
+class AccessError(Exception):
+    """A register or field used in a way its ATTRIBUTE doesn't allow."""
+
+
+class Backend:
+    """Reads and writes the bus, by byte address and size in bits.
+
+    Either method may return an awaitable, as a cocotb bus driver does. The
+    register accesses using it then return one as well, to be awaited.
+    """
+
+    def read(self, address, size):
+        raise NotImplementedError
+
+    def write(self, address, value, size):
+        raise NotImplementedError
+
+
+class CallbackBackend(Backend):
+    """A backend made of two functions, for a JTAG or UART link or a simulator."""
+
+    def __init__(self, read, write):
+        self._read = read
+        self._write = write
+
+    def read(self, address, size):
+        return self._read(address, size)
+
+    def write(self, address, value, size):
+        return self._write(address, value, size)
+
+
+class MemoryBackend(Backend):
+    """Plain memory for scripts without hardware, reading 0 where nothing was
+    written. No field acts on being read or written."""
+
+    def __init__(self):
+        self.memory = {}
+
+    def read(self, address, size):
+        return self.memory.get(address, 0)
+
+    def write(self, address, value, size):
+        self.memory[address] = value
+
+
+def _then(result, f):
+    """f(result), awaiting result first and what f returns after if needed."""
+    if not inspect.isawaitable(result):
+        return f(result)
+
+    async def wait():
+        value = f(await result)
+        if inspect.isawaitable(value):
+            value = await value
+        return value
+
+    return wait()
+
+
+class Field:
+    """The bits of a field and what reading and writing them does, in IP-XACT
+    terms. `reset` is the value after the default reset, of which only the
+    bits in `reset_mask` are known."""
+
+    def __init__(
+        self,
+        name,
+        offset,
+        width,
+        access,
+        modified_write_value=None,
+        read_action=None,
+        volatile=False,
+        reserved=False,
+        reset=0,
+        reset_mask=None,
+        description="",
+    ):
+        self.name = name
+        self.offset = offset
+        self.width = width
+        self.mask = ((1 << width) - 1) << offset
+        self.access = access
+        self.modified_write_value = modified_write_value
+        self.read_action = read_action
+        self.volatile = volatile
+        self.reserved = reserved
+        self.reset = reset
+        self.reset_mask = (1 << width) - 1 if reset_mask is None else reset_mask
+        self.description = description
+
+    @property
+    def readable(self):
+        return self.access in ("read-only", "read-write", "read-writeOnce")
+
+    @property
+    def writable(self):
+        return self.access != "read-only"
+
+    def get(self, value):
+        """The field in a register value."""
+        return (value & self.mask) >> self.offset
+
+    def put(self, value, field):
+        """The register value with the field changed to `field`."""
+        if not 0 <= field < 1 << self.width:
+            raise ValueError(f"{field:#x} doesn't fit the {self.width} bits of {self.name}")
+        return (value & ~self.mask) | (field << self.offset)
+
+    def __repr__(self):
+        return f"Field({self.name!r}, {self.offset}, {self.width}, {self.access!r})"
+
+
+class Register:
+    """A register at `address`, its fields by Python name.
+
+    Values are plain integers. Fields acting on a written 1, such as W1C ones,
+    are 0 in what `modify` and `encode` start from, and those acting on a
+    written 0 are 1, so they are left alone unless given.
+    """
+
+    def __init__(self, backend, address, name, size, fields):
+        self.backend = backend
+        self.address = address
+        self.name = name
+        self.size = size
+        self.fields = fields
+        self.reset = 0
+        self.reset_mask = 0
+        self.ones = 0
+        self.zeros = 0
+        for field in fields.values():
+            self.reset |= (field.reset & field.reset_mask) << field.offset
+            self.reset_mask |= field.reset_mask << field.offset
+            if field.modified_write_value in ("oneToClear", "oneToSet", "oneToToggle"):
+                self.ones |= field.mask
+            if field.modified_write_value in ("zeroToClear", "zeroToSet", "zeroToToggle"):
+                self.zeros |= field.mask
+
+    @property
+    def readable(self):
+        return any(field.readable for field in self.fields.values())
+
+    @property
+    def writable(self):
+        return any(field.writable for field in self.fields.values())
+
+    def read(self):
+        if not self.readable:
+            raise AccessError(f"{self.name} can't be read")
+        return self.backend.read(self.address, self.size)
+
+    def read_field(self, name):
+        """Reads the register and gives the field `name`."""
+        field = self._field(name)
+        return _then(self.read(), field.get)
+
+    def write(self, value=None, **fields):
+        """Writes `value`, or what `encode` makes of `fields`."""
+        if not self.writable:
+            raise AccessError(f"{self.name} can't be written")
+        if value is None:
+            value = self.encode(**fields)
+        elif fields:
+            raise TypeError("give either a value or fields")
+        return self.backend.write(self.address, value, self.size)
+
+    def modify(self, **fields):
+        """Reads the register, changes `fields` and writes it back."""
+        return _then(
+            self.read(),
+            lambda value: self.write(self._change((value & ~self.ones) | self.zeros, fields)),
+        )
+
+    def encode(self, **fields):
+        """The reset value with `fields` changed."""
+        return self._change((self.reset & ~self.ones) | self.zeros, fields)
+
+    def decode(self, value):
+        """The fields in `value` by name, reserved ones left out."""
+        return {
+            name: field.get(value) for name, field in self.fields.items() if not field.reserved
+        }
+
+    def _field(self, name):
+        field = self.fields.get(name)
+        if field is None or field.reserved:
+            raise KeyError(f"{self.name} has no field {name}")
+        return field
+
+    def _change(self, value, fields):
+        for name, field_value in fields.items():
+            field = self._field(name)
+            if not field.writable:
+                raise AccessError(f"{self.name}.{field.name} can't be written")
+            value = field.put(value, field_value)
+        return value
+
+    def __repr__(self):
+        return f"<Register {self.name} at {self.address:#x}>"
+
+
+class Group:
+    """Registers and register files from `base` on."""
+
+    def __init__(self, base):
+        self.base = base
+
+    def registers(self):
+        """Every register in here, in register files too, by address."""
+        found = []
+        for member in vars(self).values():
+            for member in member if isinstance(member, list) else [member]:
+                if isinstance(member, Register):
+                    found.append(member)
+                elif isinstance(member, Group):
+                    found.extend(member.registers())
+        return sorted(found, key=lambda reg: reg.address)
+"#;
+
+/// `snake_case`, for members and field names.
+fn snake(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+fn member(name: &str) -> String {
+    let mut ident = snake(name);
+    if MEMBERS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+/// `CamelCase`, for classes. Parts in capitals are lowered, as in the Rust
+/// crate.
+fn camel(name: &str) -> String {
+    let mut ident: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .map(|part| {
+            let part = if part.chars().any(|c| c.is_ascii_lowercase()) {
+                part.to_string()
+            } else {
+                part.to_ascii_lowercase()
+            };
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ident.insert(0, 'R');
+    }
+    ident
+}
+
+/// A Python string literal.
+fn string(text: &str) -> String {
+    let mut literal = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// A docstring line, which can't end in a quote or hold three.
+fn doc(text: &str) -> String {
+    format!("\"\"\"{}\"\"\"", text.replace('\\', "/").replace('"', "'"))
+}
+
+/// A Python module with a class for every block and register file of `compo`,
+/// whose registers know their fields and the ATTRIBUTE of each, and read and
+/// write through a backend given to the component class.
+pub fn to_string(compo: &Component, attrs: &AttributeTable) -> anyhow::Result<String, Error> {
+    let mut lines = vec![
+        doc(&format!(
+            "Registers of {} {}, generated by irgen.",
+            compo.name(),
+            compo.version()
+        )),
+        String::new(),
+        RUNTIME.trim_end().to_string(),
+    ];
+
+    let mut compo_class = camel(compo.name());
+    if CLASSES.contains(&compo_class.as_str()) {
+        compo_class.push('_');
+    }
+    let mut blks = Vec::new();
+    for blk in compo.blks() {
+        let mut class = camel(blk.name());
+        if class == compo_class || CLASSES.contains(&class.as_str()) {
+            class.push_str("Block");
+        }
+        let base = parse_hex(blk.offset())?;
+        let mut members = Vec::new();
+
+        for rf in blk.rfs() {
+            let rf_class = format!("{}{}", camel(blk.name()), camel(rf.name()));
+            lines.push(String::new());
+            lines.push(String::new());
+            lines.push(format!("class {}(Group):", rf_class));
+            lines.push(format!(
+                "    {}",
+                doc(&format!("{} in {}.", rf.name(), blk.name()))
+            ));
+            lines.push(String::new());
+            lines.push(format!("    RANGE = 0x{:X}", parse_hex(rf.range())?));
+            lines.push(String::new());
+            lines.push("    def __init__(self, backend, base):".into());
+            lines.push("        super().__init__(base)".into());
+            for reg in rf.regs() {
+                register(&mut lines, reg, attrs)?;
+            }
+
+            let offset = parse_hex(rf.offset())?;
+            members.push(if rf.dim() == "1" {
+                format!(
+                    "self.{} = {}(backend, base + 0x{:X})",
+                    member(rf.name()),
+                    rf_class,
+                    offset
+                )
+            } else {
+                format!(
+                    "self.{} = [{}(backend, base + 0x{:X} + n * {}.RANGE) for n in range({})]",
+                    member(rf.name()),
+                    rf_class,
+                    offset,
+                    rf_class,
+                    rf.dim()
+                )
+            });
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+        lines.push(format!("class {}(Group):", class));
+        lines.push(format!(
+            "    {}",
+            doc(&format!("{} at 0x{:X}.", blk.name(), base))
+        ));
+        lines.push(String::new());
+        lines.push(format!("    BASE = 0x{:X}", base));
+        lines.push(format!("    RANGE = 0x{:X}", parse_hex(blk.range())?));
+        lines.push(String::new());
+        lines.push("    def __init__(self, backend, base=BASE):".into());
+        lines.push("        super().__init__(base)".into());
+        for reg in blk.regs() {
+            register(&mut lines, reg, attrs)?;
+        }
+        for line in members {
+            lines.push(format!("        {}", line));
+        }
+        blks.push((member(blk.name()), class));
+    }
+
+    lines.push(String::new());
+    lines.push(String::new());
+    lines.push(format!("class {}(Group):", compo_class));
+    lines.push(format!(
+        "    {}",
+        doc(&format!(
+            "{} {}, every block at its base address.",
+            compo.name(),
+            compo.version()
+        ))
+    ));
+    lines.push(String::new());
+    lines.push(format!("    NAME = {}", string(compo.name())));
+    lines.push(format!("    VERSION = {}", string(compo.version())));
+    lines.push(String::new());
+    lines.push("    def __init__(self, backend):".into());
+    lines.push("        super().__init__(0)".into());
+    for (name, class) in blks {
+        lines.push(format!("        self.{} = {}(backend)", name, class));
+    }
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+/// The `Register` member for `reg`, in `__init__` of its block or register
+/// file.
+fn register(
+    lines: &mut Vec<String>,
+    reg: &Register,
+    attrs: &AttributeTable,
+) -> anyhow::Result<(), Error> {
+    lines.push(format!(
+        "        self.{} = Register(backend, base + 0x{:X}, {}, {}, {{",
+        member(reg.name()),
+        parse_hex(reg.offset())?,
+        string(reg.name()),
+        reg.size()
+    ));
+    for field in reg.fields() {
+        for field in field.flatten()? {
+            let attr = attrs.get(field.attr())?;
+            let width: u64 = field.width().parse()?;
+            let mut args = vec![
+                string(field.name()),
+                field.offset().to_string(),
+                field.width().to_string(),
+                string(attr.access()),
+            ];
+            if let Some(value) = attr.modified_write_value() {
+                args.push(format!("modified_write_value={}", string(value)));
+            }
+            if let Some(action) = attr.read_action() {
+                args.push(format!("read_action={}", string(action)));
+            }
+            if field.is_volatile(attrs)? {
+                args.push("volatile=True".into());
+            }
+            if field.reserved() {
+                args.push("reserved=True".into());
+            }
+            let ones = if width >= 64 {
+                u64::MAX
+            } else {
+                (1 << width) - 1
+            };
+            let (reset, known) = field.resets()[0].bits()?;
+            args.push(format!("reset=0x{:X}", reset & known & ones));
+            if known & ones != ones {
+                args.push(format!("reset_mask=0x{:X}", known & ones));
+            }
+            // the placeholder of an empty DESCRIPTION cell says nothing
+            if !matches!(field.desc().trim(), "" | "No Description") {
+                args.push(format!("description={}", string(field.desc().trim())));
+            }
+            lines.push(format!(
+                "            {}: Field({}),",
+                string(&snake(field.name())),
+                args.join(", ")
+            ));
+        }
+    }
+    lines.push("        })".into());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::golden;
+
+    #[test]
+    fn golden_module() {
+        let (compo, attrs) = golden::model();
+        golden::check("python/golden_regs.py", &to_string(&compo, &attrs).unwrap());
+    }
+}
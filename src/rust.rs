@@ -0,0 +1,799 @@
+use crate::error::Error;
+use crate::schema::attr::{Attribute, AttributeTable};
+use crate::schema::base::{Component, Field, Register, parse_hex};
+
+const KEYWORDS: [&str; 52] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield", "union",
+];
+
+/// `snake_case`, for modules, members and accessors.
+fn snake(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+/// `CamelCase`, for types. Parts in capitals are lowered, `UART_CTRL` and
+/// `uart_ctrl` both give `UartCtrl`.
+fn camel(name: &str) -> String {
+    let mut ident: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .map(|part| {
+            let part = if part.chars().any(|c| c.is_ascii_lowercase()) {
+                part.to_string()
+            } else {
+                part.to_ascii_lowercase()
+            };
+            capital(&part)
+        })
+        .collect();
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ident.insert(0, 'R');
+    }
+    ident
+}
+
+/// `1.0` as `1.0.0`, Cargo wants three numbers.
+fn crate_version(version: &str) -> String {
+    let mut parts: Vec<&str> = version.trim().split('.').collect();
+    if parts.len() > 3 || parts.iter().any(|part| part.parse::<u64>().is_err()) {
+        return "0.1.0".into();
+    }
+    parts.resize(3, "0");
+    parts.join(".")
+}
+
+/// Lines of Rust, indented by `depth` levels.
+#[derive(Default)]
+struct Out {
+    text: String,
+    depth: usize,
+}
+
+impl Out {
+    fn line(&mut self, line: impl AsRef<str>) {
+        let line = line.as_ref();
+        if !line.is_empty() {
+            self.text.push_str(&"    ".repeat(self.depth));
+            self.text.push_str(line);
+        }
+        self.text.push('\n');
+    }
+
+    fn doc(&mut self, text: &str) {
+        for line in text.lines() {
+            self.line(format!("/// {}", line.trim_end()).trim_end());
+        }
+    }
+
+    fn open(&mut self, line: impl AsRef<str>) {
+        self.line(line);
+        self.depth += 1;
+    }
+
+    fn close(&mut self, line: &str) {
+        self.depth -= 1;
+        self.line(line);
+    }
+}
+
+/// A member of a `#[repr(C)]` register block.
+struct Member {
+    offset: u64,
+    size: u64,
+    align: u64,
+    // as in the register map, for errors
+    name: String,
+    ty: String,
+}
+
+fn readable(attr: &Attribute) -> bool {
+    matches!(attr.access(), "read-only" | "read-write" | "read-writeOnce")
+}
+
+fn writable(attr: &Attribute) -> bool {
+    attr.access() != "read-only"
+}
+
+/// The crate for `compo`, as `Cargo.toml` and `src/lib.rs` with their paths in
+/// the crate. Every block is a module with a `#[repr(C)]` register block at
+/// its base address, and every register a type whose accessors follow the
+/// ATTRIBUTE of its fields.
+pub fn to_files(
+    compo: &Component,
+    attrs: &AttributeTable,
+) -> anyhow::Result<Vec<(String, &'static str)>, Error> {
+    let manifest = [
+        "# generated by irgen".to_string(),
+        "[package]".into(),
+        format!("name = \"{}-pac\"", snake(compo.name()).replace('_', "-")),
+        format!("version = \"{}\"", crate_version(compo.version())),
+        format!(
+            "description = \"Registers of {} {}\"",
+            compo.name(),
+            compo.version()
+        ),
+        "edition = \"2021\"".into(),
+        // offset_of!
+        "rust-version = \"1.77\"".into(),
+        String::new(),
+        "[dependencies]".into(),
+        String::new(),
+    ]
+    .join("\n");
+
+    let mut out = Out::default();
+    out.line(format!(
+        "//! Registers of {} {}, generated by irgen.",
+        compo.name(),
+        compo.version()
+    ));
+    out.line("#![no_std]");
+    for blk in compo.blks() {
+        let base = parse_hex(blk.offset())?;
+        let mut members = Vec::new();
+        for reg in blk.regs() {
+            members.push(register_member(reg, &camel(reg.name()))?);
+        }
+        for rf in blk.rfs() {
+            let dim: u64 = rf.dim().parse()?;
+            let range = parse_hex(rf.range())?;
+            let module = snake(rf.name());
+            members.push(Member {
+                offset: parse_hex(rf.offset())?,
+                size: dim * range,
+                align: align(rf.regs())?,
+                name: rf.name().to_string(),
+                ty: if dim == 1 {
+                    format!("{}::RegisterBlock", module)
+                } else {
+                    format!("[{}::RegisterBlock; {}]", module, dim)
+                },
+            });
+        }
+
+        out.line("");
+        out.doc(&format!("{} at 0x{:X}.", blk.name(), base));
+        out.open(format!("pub mod {} {{", snake(blk.name())));
+        out.line("/// Base address of the registers.");
+        out.line(format!("pub const BASE: usize = 0x{:X};", base));
+        out.line("");
+        register_block(&mut out, blk.name(), members, None)?;
+        out.line("");
+        out.open("impl RegisterBlock {");
+        out.line("pub const PTR: *const Self = BASE as *const Self;");
+        out.line("");
+        out.line("/// The registers at [`BASE`].");
+        out.line("///");
+        out.line("/// # Safety");
+        out.line("///");
+        out.line("/// Nothing else may access them in a way that conflicts, and they have");
+        out.line("/// to be mapped at [`BASE`].");
+        out.line("#[inline]");
+        out.open("pub unsafe fn steal() -> &'static Self {");
+        out.line("unsafe { &*Self::PTR }");
+        out.close("}");
+        out.close("}");
+        for reg in blk.regs() {
+            out.line("");
+            register(
+                &mut out,
+                &format!("{}.{}", blk.name(), reg.name()),
+                reg,
+                attrs,
+            )?;
+        }
+
+        for rf in blk.rfs() {
+            let path = format!("{}.{}", blk.name(), rf.name());
+            let range = parse_hex(rf.range())?;
+            let members = rf
+                .regs()
+                .iter()
+                .map(|reg| register_member(reg, &camel(reg.name())))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            out.line("");
+            out.doc(&format!(
+                "{} at 0x{:X} in {}, {} times 0x{:X} bytes apart.",
+                rf.name(),
+                parse_hex(rf.offset())?,
+                blk.name(),
+                rf.dim(),
+                range
+            ));
+            out.open(format!("pub mod {} {{", snake(rf.name())));
+            register_block(&mut out, &path, members, Some(range))?;
+            for reg in rf.regs() {
+                out.line("");
+                register(&mut out, &format!("{}.{}", path, reg.name()), reg, attrs)?;
+            }
+            out.close("}");
+        }
+        out.close("}");
+    }
+
+    Ok(vec![(manifest, "Cargo.toml"), (out.text, "src/lib.rs")])
+}
+
+fn register_bits(reg: &Register) -> anyhow::Result<u64, Error> {
+    let bits: u64 = reg.size().parse()?;
+    if ![8, 16, 32, 64].contains(&bits) {
+        return Err(Error::Model(format!(
+            "{}: a {}-bit register has no Rust integer type",
+            reg.name(),
+            bits
+        )));
+    }
+    Ok(bits)
+}
+
+fn register_member(reg: &Register, ty: &str) -> anyhow::Result<Member, Error> {
+    let bytes = register_bits(reg)? / 8;
+    Ok(Member {
+        offset: parse_hex(reg.offset())?,
+        size: bytes,
+        align: bytes,
+        name: reg.name().to_string(),
+        ty: ty.to_string(),
+    })
+}
+
+/// The alignment of a register file, that of its widest register.
+fn align(regs: &[Register]) -> anyhow::Result<u64, Error> {
+    regs.iter()
+        .map(|reg| Ok(register_bits(reg)? / 8))
+        .try_fold(1, |align, bytes: anyhow::Result<u64, Error>| {
+            Ok(align.max(bytes?))
+        })
+}
+
+/// A `#[repr(C)]` struct with `members` at their offsets, padded to `size`
+/// bytes if given, and checked when the crate is built.
+fn register_block(
+    out: &mut Out,
+    path: &str,
+    mut members: Vec<Member>,
+    size: Option<u64>,
+) -> anyhow::Result<(), Error> {
+    members.sort_by_key(|member| member.offset);
+    out.line("#[repr(C)]");
+    out.open("pub struct RegisterBlock {");
+    let (mut end, mut gaps) = (0, 0);
+    for member in &members {
+        if member.offset < end {
+            return Err(Error::Model(format!(
+                "{}.{}: overlaps the register before it",
+                path, member.name
+            )));
+        }
+        if member.offset % member.align != 0 {
+            return Err(Error::Model(format!(
+                "{}.{}: 0x{:X} is not a multiple of {} bytes, it can't be a Rust field",
+                path, member.name, member.offset, member.align
+            )));
+        }
+        if member.offset > end {
+            out.line(format!(
+                "_reserved{}: [u8; 0x{:X}],",
+                gaps,
+                member.offset - end
+            ));
+            gaps += 1;
+        }
+        out.line(format!("/// 0x{:X}", member.offset));
+        out.line(format!("pub {}: {},", snake(&member.name), member.ty));
+        end = member.offset + member.size;
+    }
+    if let Some(size) = size {
+        let align = members.iter().map(|member| member.align).max().unwrap_or(1);
+        if end > size || size % align != 0 {
+            return Err(Error::Model(format!(
+                "{}: its registers don't fit 0x{:X} bytes apart",
+                path, size
+            )));
+        }
+        if size > end {
+            out.line(format!("_reserved{}: [u8; 0x{:X}],", gaps, size - end));
+        }
+    }
+    out.close("}");
+
+    if members.is_empty() && size.is_none() {
+        return Ok(());
+    }
+    out.line("");
+    out.open("const _: () = {");
+    for member in &members {
+        out.line(format!(
+            "assert!(::core::mem::offset_of!(RegisterBlock, {}) == 0x{:X});",
+            snake(&member.name),
+            member.offset
+        ));
+    }
+    if let Some(size) = size {
+        out.line(format!(
+            "assert!(::core::mem::size_of::<RegisterBlock>() == 0x{:X});",
+            size
+        ));
+    }
+    out.close("};");
+    Ok(())
+}
+
+/// The register type and its value type.
+fn register(
+    out: &mut Out,
+    path: &str,
+    reg: &Register,
+    attrs: &AttributeTable,
+) -> anyhow::Result<(), Error> {
+    let bits = register_bits(reg)?;
+    let int = format!("u{}", bits);
+    let ty = camel(reg.name());
+    let value = format!("{}Value", ty);
+
+    // the elements of a field array share the enum of the array
+    let mut fields = Vec::new();
+    let mut enums = Vec::new();
+    for field in reg.fields() {
+        let enum_ty = (!field.enums().is_empty() && !field.reserved())
+            .then(|| format!("{}{}", ty, camel(field.name())));
+        if let Some(enum_ty) = &enum_ty {
+            enums.push((field, enum_ty.clone()));
+        }
+        for field in field.flatten()? {
+            let attr = attrs.get(field.attr())?;
+            fields.push((field, attr, enum_ty.clone()));
+        }
+    }
+    let mask = |field: &Field| -> anyhow::Result<u64, Error> {
+        let (offset, width): (u64, u64) = (field.offset().parse()?, field.width().parse()?);
+        if offset + width > bits {
+            return Err(Error::Model(format!(
+                "{}.{}: does not fit the register",
+                path,
+                field.name()
+            )));
+        }
+        let ones = if width == 64 {
+            u64::MAX
+        } else {
+            (1 << width) - 1
+        };
+        Ok(ones << offset)
+    };
+
+    // bits that do something when written back as read
+    let (mut ones, mut zeros, mut reset) = (0, 0, 0);
+    let (mut on_read, mut on_write) = (Vec::new(), Vec::new());
+    for (field, attr, _) in &fields {
+        let mask = mask(field)?;
+        match attr.modified_write_value() {
+            Some("oneToClear" | "oneToSet" | "oneToToggle") => ones |= mask,
+            Some("zeroToClear" | "zeroToSet" | "zeroToToggle") => zeros |= mask,
+            Some("clear") => on_write.push(format!("clears `{}`", field.name())),
+            Some("set") => on_write.push(format!("sets `{}`", field.name())),
+            _ => {}
+        }
+        match attr.read_action() {
+            Some("clear") => on_read.push(format!("clears `{}`", field.name())),
+            Some("set") => on_read.push(format!("sets `{}`", field.name())),
+            Some("modify") => on_read.push(format!("changes `{}`", field.name())),
+            _ => {}
+        }
+        let (value, known) = field.resets()[0].bits()?;
+        let offset: u64 = field.offset().parse()?;
+        reset |= ((value & known) << offset) & mask;
+    }
+    let can_read = fields.iter().any(|(_, attr, _)| readable(attr));
+    let can_write = fields.iter().any(|(_, attr, _)| writable(attr));
+
+    out.doc(&format!(
+        "`{}` at 0x{:X}.",
+        reg.name(),
+        parse_hex(reg.offset())?
+    ));
+    out.line("#[repr(transparent)]");
+    out.line(format!(
+        "pub struct {}(::core::cell::UnsafeCell<{}>);",
+        ty, int
+    ));
+    out.line("");
+    out.open(format!("impl {} {{", ty));
+    if can_read {
+        if on_read.is_empty() {
+            out.line("/// Reads the register.");
+        } else {
+            out.line(format!(
+                "/// Reads the register, which {}.",
+                on_read.join(", ")
+            ));
+        }
+        out.line("#[inline]");
+        out.open(format!("pub fn read(&self) -> {} {{", value));
+        out.line(format!(
+            "{}(unsafe {{ ::core::ptr::read_volatile(self.0.get()) }})",
+            value
+        ));
+        out.close("}");
+    }
+    if can_write {
+        if can_read {
+            out.line("");
+        }
+        if on_write.is_empty() {
+            out.line("/// Writes the register.");
+        } else {
+            out.line(format!(
+                "/// Writes the register, which {}.",
+                on_write.join(", ")
+            ));
+        }
+        out.line("#[inline]");
+        out.open(format!("pub fn write(&self, value: {}) {{", value));
+        out.line("unsafe { ::core::ptr::write_volatile(self.0.get(), value.0) }");
+        out.close("}");
+    }
+    if can_read && can_write {
+        let all = if bits == 64 {
+            u64::MAX
+        } else {
+            (1 << bits) - 1
+        };
+        // nothing read is left to keep
+        let no_read = ones | zeros == all;
+        out.line("");
+        out.line("/// Reads the register, changes the value with `f` and writes it back.");
+        if !on_read.is_empty() && !no_read {
+            out.line(format!("/// The read {}.", on_read.join(", ")));
+        }
+        if ones | zeros != 0 {
+            out.line("/// Fields acting on a written 1 are 0 and those acting on a 0 are 1 in");
+            out.line("/// what `f` gets, so they are left alone unless `f` changes them.");
+        }
+        out.line("#[inline]");
+        out.open(format!(
+            "pub fn modify(&self, f: impl FnOnce({}) -> {}) {{",
+            value, value
+        ));
+        if ones | zeros == 0 {
+            out.line("self.write(f(self.read()));");
+        } else {
+            let expr = match (ones, zeros) {
+                _ if no_read => format!("0x{:X}", zeros),
+                (ones, 0) => format!("self.read().0 & !0x{:X}", ones),
+                (0, zeros) => format!("self.read().0 | 0x{:X}", zeros),
+                (ones, zeros) => format!("(self.read().0 & !0x{:X}) | 0x{:X}", ones, zeros),
+            };
+            out.line(format!("self.write(f({}({})));", value, expr));
+        }
+        out.close("}");
+    }
+    out.close("}");
+
+    out.line("");
+    out.line(format!("/// A value of [`{}`].", ty));
+    out.line("#[derive(Clone, Copy, Debug, PartialEq, Eq)]");
+    out.line(format!("pub struct {}(pub {});", value, int));
+    out.line("");
+    out.open(format!("impl {} {{", value));
+    out.line("/// The value after a reset, with 0 for bits that have no known one.");
+    out.line(format!("pub const RESET: Self = Self(0x{:X});", reset));
+    for (field, attr, enum_ty) in &fields {
+        if field.reserved() {
+            continue;
+        }
+        accessors(out, field, attr, bits, mask(field)?, enum_ty.as_deref())?;
+    }
+    out.close("}");
+    for (field, enum_ty) in enums {
+        out.line("");
+        enumeration(
+            out,
+            &format!("{}.{}", path, field.name()),
+            field,
+            &ty,
+            enum_ty,
+            &int,
+        )?;
+    }
+    Ok(())
+}
+
+/// The ENUM values of `field` as a `#[repr]` enum that converts from and to
+/// the register's integer type.
+fn enumeration(
+    out: &mut Out,
+    path: &str,
+    field: &Field,
+    reg_ty: &str,
+    ty: String,
+    int: &str,
+) -> anyhow::Result<(), Error> {
+    let mut variants: Vec<(String, u64)> = Vec::new();
+    for value in field.enums() {
+        let mut variant = camel(value.name());
+        if variant == "Self" {
+            variant.push('_');
+        }
+        if variants.iter().any(|(known, _)| *known == variant) {
+            return Err(Error::Model(format!(
+                "{}: more than one value is named `{}` in Rust",
+                path, variant
+            )));
+        }
+        variants.push((variant, parse_hex(value.value())?));
+    }
+
+    out.line(format!(
+        "/// Values of `{}` in [`{}`].",
+        field.name(),
+        reg_ty
+    ));
+    out.line("#[derive(Clone, Copy, Debug, PartialEq, Eq)]");
+    out.line(format!("#[repr({})]", int));
+    out.open(format!("pub enum {} {{", ty));
+    for (variant, value) in &variants {
+        out.line(format!("{} = 0x{:X},", variant, value));
+    }
+    out.close("}");
+    out.line("");
+    out.open(format!("impl TryFrom<{}> for {} {{", int, ty));
+    out.line(format!("type Error = {};", int));
+    out.line("");
+    out.line("/// The variant with `value`, or `value` if none has it.");
+    out.line("#[inline]");
+    out.open(format!(
+        "fn try_from(value: {}) -> Result<Self, {}> {{",
+        int, int
+    ));
+    out.open("match value {");
+    for (variant, value) in &variants {
+        out.line(format!("0x{:X} => Ok(Self::{}),", value, variant));
+    }
+    out.line("_ => Err(value),");
+    out.close("}");
+    out.close("}");
+    out.close("}");
+    out.line("");
+    out.open(format!("impl From<{}> for {} {{", ty, int));
+    out.line("#[inline]");
+    out.open(format!("fn from(value: {}) -> Self {{", ty));
+    out.line("value as Self");
+    out.close("}");
+    out.close("}");
+    Ok(())
+}
+
+/// A getter for a readable field, and for a writable one a setter, or
+/// `clear_`, `set_` or `toggle_` for one that acts on a written 1 or 0.
+/// Fields with an `enum_ty` also get a `_variant` getter and setter.
+fn accessors(
+    out: &mut Out,
+    field: &Field,
+    attr: &Attribute,
+    bits: u64,
+    mask: u64,
+    enum_ty: Option<&str>,
+) -> anyhow::Result<(), Error> {
+    let name = snake(field.name());
+    let int = format!("u{}", bits);
+    let offset: u64 = field.offset().parse()?;
+    let width: u64 = field.width().parse()?;
+    let flag = width == 1;
+    let whole = width == bits;
+    let shifted = |value: &str| match offset {
+        0 => value.to_string(),
+        _ => format!("({} << {})", value, offset),
+    };
+    // the placeholder of an empty DESCRIPTION cell says nothing
+    let has_desc = !matches!(field.desc().trim(), "" | "No Description");
+    let doc = |out: &mut Out, extra: &str| {
+        if has_desc {
+            out.doc(field.desc());
+            if !extra.is_empty() {
+                out.line("///");
+            }
+        }
+        out.doc(extra);
+    };
+
+    if readable(attr) {
+        out.line("");
+        let read = match attr.read_action() {
+            Some("clear") => format!("Reading the register clears `{}`.", field.name()),
+            Some("set") => format!("Reading the register sets `{}`.", field.name()),
+            Some("modify") => format!("Reading the register changes `{}`.", field.name()),
+            _ => String::new(),
+        };
+        if has_desc {
+            doc(out, &read);
+        } else {
+            doc(out, format!("`{}`. {}", field.name(), read).trim_end());
+        }
+        let value = match offset {
+            0 => "self.0".to_string(),
+            _ => format!("(self.0 >> {})", offset),
+        };
+        if flag {
+            out.line(format!("pub const fn {}(self) -> bool {{", name));
+            out.line(format!("    {} & 1 != 0", value));
+        } else {
+            out.line(format!("pub const fn {}(self) -> {} {{", name, int));
+            if whole {
+                out.line("    self.0");
+            } else {
+                out.line(format!("    {} & 0x{:X}", value, mask >> offset));
+            }
+        }
+        out.line("}");
+        if let Some(enum_ty) = enum_ty {
+            out.line("");
+            out.doc(&format!(
+                "`{}` as a [`{}`], or its value if that has no variant.",
+                field.name(),
+                enum_ty
+            ));
+            out.line(format!(
+                "pub fn {}_variant(self) -> Result<{}, {}> {{",
+                name, enum_ty, int
+            ));
+            if flag {
+                out.line(format!(
+                    "    {}::try_from({}::from(self.{}()))",
+                    enum_ty, int, name
+                ));
+            } else {
+                out.line(format!("    {}::try_from(self.{}())", enum_ty, name));
+            }
+            out.line("}");
+        }
+    }
+    if !writable(attr) {
+        return Ok(());
+    }
+
+    let (verb, ones) = match attr.modified_write_value() {
+        None | Some("modify") => {
+            out.line("");
+            doc(out, &format!("Sets `{}` to `value`.", field.name()));
+            if whole {
+                out.line(format!(
+                    "pub const fn set_{}(self, value: {}) -> Self {{",
+                    name, int
+                ));
+                out.line("    Self(value)");
+            } else if flag {
+                out.line(format!(
+                    "pub const fn set_{}(self, value: bool) -> Self {{",
+                    name
+                ));
+                out.line(format!(
+                    "    Self((self.0 & !0x{:X}) | {})",
+                    mask,
+                    shifted(&format!("(value as {})", int))
+                ));
+            } else {
+                out.line(format!(
+                    "pub const fn set_{}(self, value: {}) -> Self {{",
+                    name, int
+                ));
+                out.line(format!(
+                    "    Self((self.0 & !0x{:X}) | ({} & 0x{:X}))",
+                    mask,
+                    shifted("value"),
+                    mask
+                ));
+            }
+            out.line("}");
+            if let Some(enum_ty) = enum_ty {
+                out.line("");
+                out.doc(&format!(
+                    "Sets `{}` to the value of `variant`.",
+                    field.name()
+                ));
+                out.line(format!(
+                    "pub const fn set_{}_variant(self, variant: {}) -> Self {{",
+                    name, enum_ty
+                ));
+                if flag {
+                    out.line(format!("    self.set_{}(variant as {} != 0)", name, int));
+                } else {
+                    out.line(format!("    self.set_{}(variant as {})", name, int));
+                }
+                out.line("}");
+            }
+            return Ok(());
+        }
+        Some("oneToClear") => ("clear", true),
+        Some("oneToSet") => ("set", true),
+        Some("oneToToggle") => ("toggle", true),
+        Some("zeroToClear") => ("clear", false),
+        Some("zeroToSet") => ("set", false),
+        Some("zeroToToggle") => ("toggle", false),
+        // any write does it, see `write`
+        _ => return Ok(()),
+    };
+
+    out.line("");
+    let by = if ones { "a 1" } else { "a 0" };
+    if flag {
+        doc(
+            out,
+            &format!("{}s `{}` by writing {}.", capital(verb), field.name(), by),
+        );
+        out.line(format!("pub const fn {}_{}(self) -> Self {{", verb, name));
+        if ones {
+            out.line(format!("    Self(self.0 | 0x{:X})", mask));
+        } else {
+            out.line(format!("    Self(self.0 & !0x{:X})", mask));
+        }
+    } else {
+        doc(
+            out,
+            &format!(
+                "{}s the bits of `{}` set in `bits` by writing {} to them.",
+                capital(verb),
+                field.name(),
+                by
+            ),
+        );
+        out.line(format!(
+            "pub const fn {}_{}(self, bits: {}) -> Self {{",
+            verb, name, int
+        ));
+        let bits = if whole {
+            "bits".to_string()
+        } else {
+            format!("({} & 0x{:X})", shifted("bits"), mask)
+        };
+        if ones {
+            out.line(format!("    Self(self.0 | {})", bits));
+        } else {
+            out.line(format!("    Self(self.0 & !{})", bits));
+        }
+    }
+    out.line("}");
+    Ok(())
+}
+
+fn capital(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::golden;
+
+    #[test]
+    fn golden_crate() {
+        let (compo, attrs) = golden::model();
+        for (text, file) in to_files(&compo, &attrs).unwrap() {
+            golden::check(&format!("rust/{}", file), &text);
+        }
+    }
+}
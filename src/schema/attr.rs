@@ -1,37 +1,226 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
 use crate::error::Error;
 
-pub fn extract_access_value(attr: &str) -> anyhow::Result<String, Error> {
-    match attr.to_ascii_uppercase().as_str() {
-        "RO" => Ok("read-only".into()),
-        "RW" | "RC" | "RS" | "WRC" | "WRS" | "WSRC" | "WCRS" | "W1C" | "W1S" | "W1T" | "W0C"
-        | "W0S" | "W0T" | "W1SRC" | "W1CRS" | "W0SRC" | "W0CRS" => Ok("read-write".into()),
-        "WO" | "WC" | "WS" | "WOC" | "WOS" => Ok("write-only".into()),
-        "W1" | "WO1" => Ok("writeOnce".into()),
-        _ => Err(Error::NotFound(attr.into())),
+const BUILTIN: &str = include_str!("attr.toml");
+
+const ACCESS: [&str; 5] = [
+    "read-only",
+    "write-only",
+    "read-write",
+    "writeOnce",
+    "read-writeOnce",
+];
+const MODIFIED_WRITE_VALUE: [&str; 9] = [
+    "oneToClear",
+    "oneToSet",
+    "oneToToggle",
+    "zeroToClear",
+    "zeroToSet",
+    "zeroToToggle",
+    "clear",
+    "set",
+    "modify",
+];
+const READ_ACTION: [&str; 3] = ["clear", "set", "modify"];
+// the UVM access policies
+const REGVUE_ACCESS: [&str; 25] = [
+    "ro", "rw", "rc", "rs", "wrc", "wrs", "wc", "ws", "wsrc", "wcrs", "w1c", "w1s", "w1t", "w0c",
+    "w0s", "w0t", "w1src", "w1crs", "w0src", "w0crs", "wo", "woc", "wos", "w1", "wo1",
+];
+
+fn default_testable() -> bool {
+    true
+}
+
+/// What an ATTRIBUTE code means, in IP-XACT terms and as a RegVue access.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Attribute {
+    access: String,
+    #[serde(default)]
+    regvue: Option<String>,
+    #[serde(default)]
+    modified_write_value: Option<String>,
+    #[serde(default)]
+    read_action: Option<String>,
+    #[serde(default = "default_testable")]
+    testable: bool,
+    #[serde(default)]
+    volatile: bool,
+}
+
+impl Attribute {
+    pub fn access(&self) -> &str {
+        &self.access
+    }
+    pub fn modified_write_value(&self) -> Option<&str> {
+        self.modified_write_value.as_deref()
     }
+    pub fn read_action(&self) -> Option<&str> {
+        self.read_action.as_deref()
+    }
+    pub fn testable(&self) -> bool {
+        self.testable
+    }
+    pub fn volatile(&self) -> bool {
+        self.volatile
+    }
+
+    fn validate(&self, code: &str) -> anyhow::Result<(), Error> {
+        let check = |value: &str, allowed: &[&str], key: &str| {
+            if allowed.contains(&value) {
+                Ok(())
+            } else {
+                Err(Error::Attribute(format!(
+                    "{}: `{}` is not a valid {}",
+                    code, value, key
+                )))
+            }
+        };
+        check(&self.access, &ACCESS, "access")?;
+        if let Some(value) = &self.modified_write_value {
+            check(value, &MODIFIED_WRITE_VALUE, "modified_write_value")?;
+        }
+        if let Some(value) = &self.read_action {
+            check(value, &READ_ACTION, "read_action")?;
+        }
+        if let Some(value) = &self.regvue {
+            check(value, &REGVUE_ACCESS, "regvue access")?;
+        }
+        Ok(())
+    }
+}
+
+/// The ATTRIBUTE codes every writer understands, the built-in ones plus
+/// those of an optional user file.
+#[derive(Debug, Clone)]
+pub struct AttributeTable {
+    attrs: HashMap<String, Attribute>,
 }
 
-pub fn extract_modified_write_value(attr: &str) -> Result<Option<String>, Error> {
-    match attr.to_ascii_uppercase().as_str() {
-        "RO" | "RW" | "RC" | "RS" | "WO" | "W1" | "WO1" => Ok(None),
-        "WRC" | "W1C" | "WCRS" | "W1CRS" => Ok(Some("oneToClear".into())),
-        "WRS" | "W1S" | "WSRC" | "W1SRC" => Ok(Some("oneToSet".into())),
-        "W1T" => Ok(Some("oneToToggle".into())),
-        "W0C" | "W0CRS" => Ok(Some("zeroToClear".into())),
-        "W0S" | "W0SRC" => Ok(Some("zeroToSet".into())),
-        "W0T" => Ok(Some("zeroToToggle".into())),
-        "WC" | "WOC" => Ok(Some("clear".into())),
-        "WS" | "WOS" => Ok(Some("set".into())),
-        _ => Err(Error::NotFound(attr.into())),
+impl AttributeTable {
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self, Error> {
+        let mut table = Self {
+            attrs: HashMap::new(),
+        };
+        table.extend(BUILTIN)?;
+        if let Some(path) = path {
+            table.extend(&fs::read_to_string(path)?)?;
+        }
+        Ok(table)
+    }
+
+    fn extend(&mut self, toml_str: &str) -> anyhow::Result<(), Error> {
+        let attrs: HashMap<String, Attribute> = toml::from_str(toml_str)?;
+        let attrs = attrs
+            .into_iter()
+            .map(|(code, mut attr)| {
+                attr.validate(&code)?;
+                // a code without a RegVue access takes that of a known code
+                // with the same meaning
+                if attr.regvue.is_none() {
+                    attr.regvue = self
+                        .find(
+                            &attr.access,
+                            attr.modified_write_value.as_deref(),
+                            attr.read_action.as_deref(),
+                        )
+                        .and_then(|known| self.attrs[known].regvue.clone());
+                }
+                Ok((code.to_ascii_uppercase(), attr))
+            })
+            .collect::<anyhow::Result<Vec<_>, Error>>()?;
+        self.attrs.extend(attrs);
+        Ok(())
+    }
+
+    /// The code for an IP-XACT access, modifiedWriteValue and readAction, the
+    /// shortest one if several codes mean the same.
+    pub fn find(
+        &self,
+        access: &str,
+        modified_write_value: Option<&str>,
+        read_action: Option<&str>,
+    ) -> Option<&str> {
+        self.attrs
+            .iter()
+            .filter(|(_, attr)| {
+                attr.access == access
+                    && attr.modified_write_value.as_deref() == modified_write_value
+                    && attr.read_action.as_deref() == read_action
+            })
+            .map(|(code, _)| code.as_str())
+            .min_by_key(|code| (code.len(), *code))
+    }
+
+    /// The RegVue access of `code`, which a code of an `--attributes` file
+    /// only lacks if no known code means the same.
+    pub fn regvue_access(&self, code: &str) -> anyhow::Result<&str, Error> {
+        self.get(code)?.regvue.as_deref().ok_or_else(|| {
+            Error::Attribute(format!(
+                "{}: no RegVue access, give it one with `regvue = \"...\"`",
+                code
+            ))
+        })
+    }
+
+    pub fn get(&self, code: &str) -> anyhow::Result<&Attribute, Error> {
+        self.attrs
+            .get(&code.trim().to_ascii_uppercase())
+            .ok_or_else(|| Error::NotFound(code.into()))
     }
 }
 
-pub fn extract_read_action_value(attr: &str) -> Result<Option<String>, Error> {
-    match attr.to_ascii_uppercase().as_str() {
-        "RO" | "RW" | "WC" | "WS" | "W1C" | "W1S" | "W1T" | "W0C" | "W0S" | "W0T" | "WO"
-        | "WOC" | "WOS" | "W1" | "WO1" => Ok(None),
-        "RC" | "WRC" | "WSRC" | "W1SRC" | "W0SRC" => Ok(Some("clear".into())),
-        "RS" | "WRS" | "WCRS" | "W1CRS" | "W0CRS" => Ok(Some("set".into())),
-        _ => Err(Error::NotFound(attr.into())),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(toml_str: &str) -> anyhow::Result<AttributeTable, Error> {
+        let mut table = AttributeTable::load(None)?;
+        table.extend(toml_str)?;
+        Ok(table)
+    }
+
+    #[test]
+    fn builtin_regvue_access() {
+        let table = AttributeTable::load(None).unwrap();
+        assert_eq!(table.regvue_access("w1c").unwrap(), "w1c");
+        assert_eq!(table.regvue_access("WO1").unwrap(), "wo1");
+    }
+
+    #[test]
+    fn regvue_access_of_the_same_meaning() {
+        let table = table(
+            r#"
+            [RWP]
+            access = "read-write"
+
+            [RW1C_HW]
+            access = "read-write"
+            modified_write_value = "oneToClear"
+
+            [RW1C]
+            access = "read-write"
+            modified_write_value = "oneToClear"
+            read_action = "clear"
+            regvue = "wrc"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(table.regvue_access("RWP").unwrap(), "rw");
+        assert_eq!(table.regvue_access("RW1C_HW").unwrap(), "w1c");
+        assert_eq!(table.regvue_access("RW1C").unwrap(), "wrc");
+    }
+
+    #[test]
+    fn no_regvue_access() {
+        let roc = table("[ROC]\naccess = \"read-only\"\nread_action = \"clear\"").unwrap();
+        assert!(roc.regvue_access("ROC").is_err());
+        assert!(table("[X]\naccess = \"read-only\"\nregvue = \"r\"").is_err());
     }
 }
@@ -3,43 +3,184 @@ use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::error::Error;
+use crate::parser::{RESET_PREFIX, parse_number};
+use crate::parser::enums::{check_enums, parse_enums};
+use crate::parser::expand::Pattern;
+use crate::parser::reset::parse_reset;
+use crate::schema::attr::AttributeTable;
+use crate::schema::reserved::{Reserved, ReservedPolicy};
+
+// The serde form is also the YAML/JSON/TOML input format, numbers are taken
+// as numbers or strings and the rarely used keys may be left out.
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Component {
     vendor: String,
     library: String,
     name: String,
+    #[serde(deserialize_with = "de_dec")]
     version: String,
     blks: Vec<Block>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Block {
     name: String,
+    #[serde(deserialize_with = "de_hex")]
     offset: String,
+    #[serde(deserialize_with = "de_hex")]
     range: String,
+    #[serde(default = "default_size", deserialize_with = "de_dec")]
     size: String,
+    #[serde(default)]
     regs: Vec<Register>,
+    #[serde(default)]
+    rfs: Vec<RegisterFile>,
 }
 
+/// A group of registers, repeated `dim` times `range` bytes apart.
+/// Register offsets are relative to the register file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegisterFile {
+    name: String,
+    #[serde(deserialize_with = "de_hex")]
+    offset: String,
+    #[serde(default = "default_dim", deserialize_with = "de_dec")]
+    dim: String,
+    #[serde(deserialize_with = "de_hex")]
+    range: String,
+    regs: Vec<Register>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Register {
     name: String,
+    #[serde(deserialize_with = "de_hex")]
     offset: String,
+    #[serde(default = "default_size", deserialize_with = "de_dec")]
     size: String,
     fields: Vec<Field>,
 }
 
+/// A field, or `dim` fields `stride` bits apart named `name_0`, `name_1`, ...
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Field {
     name: String,
+    #[serde(deserialize_with = "de_dec")]
     offset: String,
+    #[serde(deserialize_with = "de_dec")]
     width: String,
+    #[serde(default = "default_dim", deserialize_with = "de_dec")]
+    dim: String,
+    // empty means `width`, the fields are packed
+    #[serde(default, deserialize_with = "de_dec")]
+    stride: String,
     attr: String,
-    reset: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hw_access: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    volatile: Option<bool>,
+    #[serde(default)]
+    reserved: bool,
+    resets: Vec<Reset>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    enums: Vec<EnumValue>,
+    #[serde(default = "default_desc")]
     desc: String,
 }
 
+/// A named value of a field, from the ENUM column.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnumValue {
+    name: String,
+    #[serde(deserialize_with = "de_hex")]
+    value: String,
+}
+
+/// The value of a field after one reset, `domain` is `None` for the DEFAULT
+/// column and the name of the domain for a `DEFAULT:<domain>` column. Bits
+/// cleared in `mask` have no known value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Reset {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    domain: Option<String>,
+    #[serde(deserialize_with = "de_reset")]
+    value: String,
+    #[serde(
+        default,
+        deserialize_with = "de_opt_hex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    mask: Option<String>,
+}
+
+fn default_size() -> String {
+    "32".into()
+}
+
+fn default_dim() -> String {
+    "1".into()
+}
+
+fn default_desc() -> String {
+    "No Description".into()
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Int(u64),
+    Float(f64),
+    Str(String),
+}
+
+fn de_number<'de, D>(de: D, int: fn(u64) -> String) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Number::deserialize(de)? {
+        Number::Int(n) => int(n),
+        // version = 1.0
+        Number::Float(n) => format!("{:?}", n),
+        Number::Str(s) => s,
+    })
+}
+
+fn de_dec<'de, D: serde::Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+    de_number(de, |n| n.to_string())
+}
+
+// addresses and values are hex strings in the model
+fn de_hex<'de, D: serde::Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+    de_number(de, |n| format!("0x{:X}", n))
+}
+
+// reset values are read like DEFAULT cells, `10` is decimal
+fn de_reset<'de, D: serde::Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+    let value = de_hex(de)?;
+    Ok(match parse_reset(&value, 64) {
+        Ok(None) => parse_number(&value).map_or(value, |n| format!("0x{:X}", n)),
+        _ => value,
+    })
+}
+
+fn de_opt_hex<'de, D: serde::Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
+    Ok(
+        Option::<Number>::deserialize(de)?.map(|number| match number {
+            Number::Int(n) => format!("0x{:X}", n),
+            Number::Float(n) => format!("{:?}", n),
+            Number::Str(s) => s,
+        }),
+    )
+}
+
 impl Component {
     pub fn vendor(&self) -> &str {
         &self.vendor
@@ -56,6 +197,129 @@ impl Component {
     pub fn blks(&self) -> &Vec<Block> {
         &self.blks
     }
+
+    /// The named reset domains of all fields, in the order they first appear.
+    pub fn reset_types(&self) -> Vec<&str> {
+        let mut types: Vec<&str> = Vec::new();
+        let regs = self.blks.iter().flat_map(|blk| {
+            blk.regs
+                .iter()
+                .chain(blk.rfs.iter().flat_map(|rf| rf.regs.iter()))
+        });
+        for reset in regs
+            .flat_map(|reg| reg.fields.iter())
+            .flat_map(|field| field.resets.iter())
+        {
+            if let Some(domain) = reset.domain()
+                && !types.contains(&domain)
+            {
+                types.push(domain);
+            }
+        }
+        types
+    }
+
+    /// Drops or rewrites the reserved fields of every register.
+    pub fn apply_reserved(&mut self, reserved: &Reserved) {
+        let regs = self.blks.iter_mut().flat_map(|blk| {
+            blk.regs
+                .iter_mut()
+                .chain(blk.rfs.iter_mut().flat_map(|rf| rf.regs.iter_mut()))
+        });
+        for reg in regs {
+            reg.fields.retain_mut(|field| {
+                if !reserved.is_reserved(&field.name, &field.attr) {
+                    return true;
+                }
+                match reserved.policy() {
+                    ReservedPolicy::Drop => return false,
+                    ReservedPolicy::Keep => {}
+                    ReservedPolicy::ReadOnly => {
+                        for reset in &mut field.resets {
+                            reset.value = "0".into();
+                            reset.mask = None;
+                        }
+                        field.attr = "RO".into();
+                    }
+                }
+                // a marker says nothing about access
+                if Reserved::is_marker(&field.attr) {
+                    field.attr = "RO".into();
+                }
+                field.reserved = true;
+                true
+            });
+        }
+    }
+
+    /// Checks a register map, whether read from sheets or from a model. All
+    /// problems are reported at once.
+    pub fn validate(&self, attrs: &AttributeTable) -> anyhow::Result<(), Error> {
+        let mut problems = Vec::new();
+        for blk in &self.blks {
+            let (Ok(_), Ok(range)) = (parse_hex(&blk.offset), parse_hex(&blk.range)) else {
+                problems.push(format!("{}: bad offset or range", blk.name));
+                continue;
+            };
+            // every register and register file array of the block, by address
+            let mut spans = Vec::new();
+            for reg in &blk.regs {
+                problems.extend(reg.problems(&blk.name, range, attrs));
+                spans.extend(reg.span().map(|(start, end)| (start, end, reg.name.as_str())));
+            }
+            for rf in &blk.rfs {
+                let path = format!("{}.{}", blk.name, rf.name);
+                let (Ok(offset), Ok(dim), Ok(rf_range)) = (
+                    parse_hex(&rf.offset),
+                    rf.dim.parse::<u64>(),
+                    parse_hex(&rf.range),
+                ) else {
+                    problems.push(format!("{}: bad offset, dim or range", path));
+                    continue;
+                };
+                let end = dim.checked_mul(rf_range).and_then(|n| n.checked_add(offset));
+                if end.is_none_or(|end| end > range) {
+                    problems.push(format!("{}: outside of {}", path, blk.name));
+                }
+                spans.extend(end.map(|end| (offset, end, rf.name.as_str())));
+                let mut rf_spans = Vec::new();
+                for reg in &rf.regs {
+                    problems.extend(reg.problems(&path, rf_range, attrs));
+                    rf_spans.extend(reg.span().map(|(start, end)| (start, end, reg.name.as_str())));
+                }
+                problems.extend(overlaps(&path, rf_spans));
+            }
+            problems.extend(overlaps(&blk.name, spans));
+        }
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+        for problem in &problems {
+            tracing::error!("{}", problem);
+        }
+        Err(Error::Model(problems.join("; ")))
+    }
+}
+
+/// The members of `parent` whose `(start, end, name)` address spans overlap
+/// one that starts before them.
+fn overlaps(parent: &str, mut spans: Vec<(u64, u64, &str)>) -> Vec<String> {
+    spans.sort_by_key(|&(start, end, _)| (start, end));
+    let mut problems = Vec::new();
+    let mut last: Option<(u64, &str)> = None;
+    for (start, end, name) in spans {
+        match last {
+            Some((last_end, other)) if start < last_end => {
+                problems.push(format!("{}.{}: overlaps {}", parent, name, other));
+                if end > last_end {
+                    last = Some((end, name));
+                }
+            }
+            _ => last = Some((end, name)),
+        }
+    }
+    problems
 }
 
 impl Block {
@@ -74,6 +338,37 @@ impl Block {
     pub fn regs(&self) -> &Vec<Register> {
         &self.regs
     }
+    pub fn rfs(&self) -> &Vec<RegisterFile> {
+        &self.rfs
+    }
+
+    /// The block as part of the IP instance `instance`, named
+    /// `<instance>_<block>` and moved up by `base`.
+    pub fn relocate(&self, instance: &str, base: u64) -> anyhow::Result<Block, Error> {
+        Ok(Block {
+            name: format!("{}_{}", instance, self.name),
+            offset: format!("0x{:X}", base + parse_hex(&self.offset)?),
+            ..self.clone()
+        })
+    }
+}
+
+impl RegisterFile {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn offset(&self) -> &str {
+        &self.offset
+    }
+    pub fn dim(&self) -> &str {
+        &self.dim
+    }
+    pub fn range(&self) -> &str {
+        &self.range
+    }
+    pub fn regs(&self) -> &Vec<Register> {
+        &self.regs
+    }
 }
 
 impl Register {
@@ -91,6 +386,106 @@ impl Register {
     }
 }
 
+impl Register {
+    /// What is wrong with the register at `parent`, which is `span` bytes large.
+    /// The bytes the register takes, if its offset and size are numbers.
+    fn span(&self) -> Option<(u64, u64)> {
+        let offset = parse_hex(&self.offset).ok()?;
+        let size = self.size.parse::<u64>().ok()?;
+        Some((offset, offset.checked_add(size / 8)?))
+    }
+
+    fn problems(&self, parent: &str, span: u64, attrs: &AttributeTable) -> Vec<String> {
+        let path = format!("{}.{}", parent, self.name);
+        // numbers that don't parse are a problem like any other
+        self.check(parent, &path, span, attrs)
+            .unwrap_or_else(|err| vec![format!("{}: {}", path, err)])
+    }
+
+    fn check(
+        &self,
+        parent: &str,
+        path: &str,
+        span: u64,
+        attrs: &AttributeTable,
+    ) -> anyhow::Result<Vec<String>, Error> {
+        let mut problems = Vec::new();
+        let size = self.size.parse::<u64>()?;
+        let end = parse_hex(&self.offset)?.checked_add(size / 8);
+        if end.is_none_or(|end| end > span) {
+            problems.push(format!("{}: outside of {}", path, parent));
+        }
+
+        let mut used: Vec<(u64, u64)> = Vec::new();
+        for field in &self.fields {
+            let field_path = format!("{}.{}", path, field.name);
+            if let Err(err) = attrs.get(&field.attr) {
+                problems.push(format!("{}: {}", field_path, err));
+            }
+            if let Some(hw_access) = &field.hw_access
+                && !["r", "w", "rw", "na"].contains(&hw_access.as_str())
+            {
+                problems.push(format!("{}: bad hw_access `{}`", field_path, hw_access));
+            }
+            match field.resets.first() {
+                Some(reset) if reset.domain.is_none() => {}
+                None => problems.push(format!("{}: has no reset", field_path)),
+                Some(_) => problems.push(format!(
+                    "{}: the first reset must have no domain",
+                    field_path
+                )),
+            }
+            if field
+                .resets
+                .iter()
+                .skip(1)
+                .any(|reset| reset.domain.is_none())
+            {
+                problems.push(format!(
+                    "{}: only the first reset may have no domain",
+                    field_path
+                ));
+            }
+            let width = field.width.parse::<u64>()?;
+            for reset in &field.resets {
+                if let Err(err) = reset.check(width) {
+                    problems.push(format!("{}: {}", field_path, err));
+                }
+            }
+            let enums = field
+                .enums
+                .iter()
+                .map(|value| Ok((value.name.as_str(), parse_hex(&value.value)?)))
+                .collect::<anyhow::Result<Vec<_>, Error>>()?;
+            if let Err(err) = check_enums(enums, width) {
+                problems.push(format!("{}: {}", field_path, err));
+            }
+
+            // the whole array has to fit before it is flattened
+            let (dim, stride) = (field.dim.parse::<u64>()?, field.stride()?);
+            let offset = field.offset.parse::<u64>()?;
+            let end = dim
+                .checked_sub(1)
+                .and_then(|n| n.checked_mul(stride))
+                .and_then(|n| n.checked_add(offset))
+                .and_then(|n| n.checked_add(width));
+            if width == 0 || end.is_none_or(|end| end > size) {
+                problems.push(format!("{}: bits outside of the register", field_path));
+                continue;
+            }
+            for field in field.flatten()? {
+                let offset = field.offset.parse::<u64>()?;
+                let end = offset + field.width.parse::<u64>()?;
+                if used.iter().any(|&(lsb, msb)| offset < msb && lsb < end) {
+                    problems.push(format!("{}: overlaps another field", field_path));
+                }
+                used.push((offset, end));
+            }
+        }
+        Ok(problems)
+    }
+}
+
 impl Field {
     pub fn name(&self) -> &str {
         &self.name
@@ -104,84 +499,385 @@ impl Field {
     pub fn attr(&self) -> &str {
         &self.attr
     }
+    pub fn hw_access(&self) -> Option<&str> {
+        self.hw_access.as_deref()
+    }
+    pub fn reserved(&self) -> bool {
+        self.reserved
+    }
+
+    /// VOLATILE if given, else whether hardware writes the field, else what the
+    /// attribute says.
+    pub fn is_volatile(&self, attrs: &AttributeTable) -> anyhow::Result<bool, Error> {
+        match (self.volatile, self.hw_access()) {
+            (Some(volatile), _) => Ok(volatile),
+            (None, Some(hw_access)) => Ok(hw_access.contains('w')),
+            (None, None) => Ok(attrs.get(&self.attr)?.volatile()),
+        }
+    }
+    /// The DEFAULT column.
     pub fn reset(&self) -> &str {
-        &self.reset
+        &self.resets[0].value
+    }
+    /// The DEFAULT column first, then the named domains of the field.
+    pub fn resets(&self) -> &Vec<Reset> {
+        &self.resets
+    }
+    /// The named values of the ENUM column, empty if it has none.
+    pub fn enums(&self) -> &Vec<EnumValue> {
+        &self.enums
     }
     pub fn desc(&self) -> &str {
         &self.desc
     }
+
+    fn stride(&self) -> anyhow::Result<u64, Error> {
+        if self.stride.is_empty() {
+            return Ok(self.width.parse()?);
+        }
+        Ok(self.stride.parse()?)
+    }
+
+    /// The single fields of an array, for outputs that have no field arrays.
+    pub fn flatten(&self) -> anyhow::Result<Vec<Field>, Error> {
+        let dim = self.dim.parse::<u64>()?;
+        if dim == 1 {
+            return Ok(vec![self.clone()]);
+        }
+        let offset = self.offset.parse::<u64>()?;
+        let stride = self.stride()?;
+        Ok((0..dim)
+            .map(|n| Field {
+                name: format!("{}_{}", self.name, n),
+                offset: (offset + n * stride).to_string(),
+                dim: "1".into(),
+                stride: self.width.clone(),
+                ..self.clone()
+            })
+            .collect())
+    }
 }
 
-pub fn df_to_regs(df: DataFrame) -> anyhow::Result<Vec<Register>, Error> {
-    (0..df.height())
-        .map(|i| {
-            let extract_str = |col_name: &str| -> anyhow::Result<String, Error> {
-                Ok(df
-                    .column(col_name)?
-                    .str()?
-                    .get(i)
-                    .map(|s| s.into())
-                    .ok_or_else(|| PolarsError::NoData("No data in DataFrame".into()))?)
-            };
+impl EnumValue {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
 
-            let name = extract_str("REG")?;
-            let offset = extract_str("ADDR")?;
-            let size = extract_str("REG_WIDTH")?;
-
-            // not consume df
-            let extract_list =
-                |col_name: &str, idx: usize| -> anyhow::Result<Vec<String>, PolarsError> {
-                    df.column(col_name)?
-                        .list()?
-                        .get_as_series(idx)
-                        .ok_or_else(|| PolarsError::NoData("No data at index".into()))?
-                        .str()?
-                        .into_iter()
-                        .map(|opt_s| {
-                            opt_s.map(|s| s.into()).ok_or_else(|| {
-                                PolarsError::NoData("No data found in dataframe".into())
-                            })
-                        })
-                        .collect()
-                };
+impl Reset {
+    /// `path` names the field in errors. The value is kept as hex whether
+    /// the cell is decimal, hex, binary or Verilog.
+    fn new(
+        path: &str,
+        domain: Option<String>,
+        cell: &str,
+        width: &str,
+    ) -> anyhow::Result<Self, Error> {
+        let width: u64 = width.parse()?;
+        let invalid = |message: String| Error::Cell(format!("{}: {}", path, message));
+        let (value, mask) = match parse_reset(cell, width).map_err(invalid)? {
+            Some((value, mask)) => (format!("0x{:X}", value), Some(format!("0x{:X}", mask))),
+            None => match parse_number(cell) {
+                Some(value) if width >= 64 || value >> width == 0 => {
+                    (format!("0x{:X}", value), None)
+                }
+                Some(_) => {
+                    return Err(invalid(format!(
+                        "`{}` does not fit in {} bits",
+                        cell.trim(),
+                        width
+                    )));
+                }
+                None => {
+                    return Err(invalid(format!(
+                        "`{}` is not a valid reset value",
+                        cell.trim()
+                    )));
+                }
+            },
+        };
+        Ok(Self {
+            domain,
+            value,
+            mask,
+        })
+    }
 
-            let name_array = extract_list("FIELD", i)?;
-            let offset_array = extract_list("BIT_OFFSET", i)?;
-            let width_array = extract_list("WIDTH", i)?;
-            let attribute_array = extract_list("ATTRIBUTE", i)?;
-            let default_array = extract_list("DEFAULT", i)?;
-            let description_array = extract_list("DESCRIPTION", i)?;
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+    pub fn mask(&self) -> Option<&str> {
+        self.mask.as_deref()
+    }
 
-            let fields = name_array
-                .iter()
-                .zip(offset_array.iter())
-                .zip(width_array.iter())
-                .zip(attribute_array.iter())
-                .zip(default_array.iter())
-                .zip(description_array.iter())
-                .map(|(((((name, offset), width), attr), reset), desc)| Field {
-                    name: name.into(),
-                    offset: offset.into(),
-                    width: width.into(),
-                    attr: attr.into(),
-                    reset: reset.into(),
-                    desc: desc.into(),
+    /// The value and the bits of it that are known.
+    pub fn bits(&self) -> anyhow::Result<(u64, u64), Error> {
+        let invalid = || Error::Model(format!("`{}` is not a valid reset value", self.value));
+        match self.mask() {
+            Some(mask) => Ok((
+                parse_hex(self.value()).map_err(|_| invalid())?,
+                parse_hex(mask)
+                    .map_err(|_| Error::Model(format!("`{}` is not a valid mask", mask)))?,
+            )),
+            // a map file may still hold `0x?F` where a sheet gives value and mask
+            None => match parse_reset(self.value(), 64) {
+                Ok(Some((value, mask))) => Ok((value, mask)),
+                _ => Ok((parse_hex(self.value()).map_err(|_| invalid())?, u64::MAX)),
+            },
+        }
+    }
+
+    /// Whether the value, and the mask if there is one, are numbers that fit
+    /// in a field `width` bits wide.
+    fn check(&self, width: u64) -> Result<(), String> {
+        let fits = |n: u64| width >= 64 || n >> width == 0;
+        let value = match &self.mask {
+            Some(mask) => match parse_hex(mask) {
+                Ok(mask) if fits(mask) => parse_hex(&self.value),
+                Ok(_) => return Err(format!("mask `{}` does not fit in {} bits", mask, width)),
+                Err(_) => return Err(format!("`{}` is not a valid mask", mask)),
+            },
+            None => match parse_reset(&self.value, width)? {
+                Some(_) => return Ok(()),
+                None => parse_hex(&self.value),
+            },
+        };
+        match value {
+            Ok(value) if fits(value) => Ok(()),
+            Ok(_) => Err(format!("`{}` does not fit in {} bits", self.value, width)),
+            Err(_) => Err(format!("`{}` is not a valid reset value", self.value)),
+        }
+    }
+}
+
+fn df_to_reg(df: &DataFrame, i: usize) -> anyhow::Result<Register, Error> {
+    let extract_str = |col_name: &str| -> anyhow::Result<String, Error> {
+        Ok(df
+            .column(col_name)?
+            .str()?
+            .get(i)
+            .map(|s| s.into())
+            .ok_or_else(|| PolarsError::NoData("No data in DataFrame".into()))?)
+    };
+
+    let name = extract_str("REG")?;
+    let offset = extract_str("ADDR")?;
+    let size = extract_str("REG_WIDTH")?;
+
+    // not consume df
+    let extract_list =
+        |col_name: &str, idx: usize| -> anyhow::Result<Vec<String>, PolarsError> {
+            df.column(col_name)?
+                .list()?
+                .get_as_series(idx)
+                .ok_or_else(|| PolarsError::NoData("No data at index".into()))?
+                .str()?
+                .into_iter()
+                .map(|opt_s| {
+                    opt_s.map(|s| s.into()).ok_or_else(|| {
+                        PolarsError::NoData("No data found in dataframe".into())
+                    })
                 })
-                .collect();
+                .collect()
+        };
 
-            Ok(Register {
-                name,
-                offset,
-                size,
-                fields,
+    let name_array = extract_list("FIELD", i)?;
+    let offset_array = extract_list("BIT_OFFSET", i)?;
+    let width_array = extract_list("WIDTH", i)?;
+    let dim_array = extract_list("FIELD_DIM", i)?;
+    let stride_array = extract_list("FIELD_STRIDE", i)?;
+    let attribute_array = extract_list("ATTRIBUTE", i)?;
+    let default_array = extract_list("DEFAULT", i)?;
+    let description_array = extract_list("DESCRIPTION", i)?;
+
+    let extract_opt_list = |col_name: &str| -> anyhow::Result<Vec<Option<String>>, PolarsError> {
+        Ok(df
+            .column(col_name)?
+            .list()?
+            .get_as_series(i)
+            .ok_or_else(|| PolarsError::NoData("No data at index".into()))?
+            .str()?
+            .into_iter()
+            .map(|opt_s| opt_s.map(String::from))
+            .collect())
+    };
+    let hw_access_array = extract_opt_list("HW_ACCESS")?;
+    let volatile_array = extract_opt_list("VOLATILE")?;
+    let enum_array = extract_opt_list("ENUM")?;
+
+    // an empty DEFAULT:<domain> cell leaves the field alone on that reset
+    let domain_arrays = df
+        .get_column_names()
+        .into_iter()
+        .filter_map(|name| {
+            let domain = name.strip_prefix(RESET_PREFIX)?.trim();
+            Some((domain.to_owned(), name.as_str()))
+        })
+        .map(|(domain, name)| Ok((domain, extract_opt_list(name)?)))
+        .collect::<anyhow::Result<Vec<_>, PolarsError>>()?;
+
+    let fields = (0..name_array.len())
+        .map(|k| -> anyhow::Result<Field, Error> {
+            let path = format!("{}.{}", name, name_array[k]);
+            let width = &width_array[k];
+            let mut resets = vec![Reset::new(&path, None, &default_array[k], width)?];
+            for (domain, values) in &domain_arrays {
+                if let Some(value) = &values[k] {
+                    resets.push(Reset::new(&path, Some(domain.clone()), value, width)?);
+                }
+            }
+            let enums = match &enum_array[k] {
+                Some(cell) => parse_enums(cell, width.parse()?)
+                    .map_err(|message| Error::Cell(format!("{}: {}", path, message)))?
+                    .into_iter()
+                    .map(|(name, value)| EnumValue {
+                        name,
+                        value: format!("0x{:X}", value),
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+            Ok(Field {
+                name: name_array[k].clone(),
+                offset: offset_array[k].clone(),
+                width: width_array[k].clone(),
+                dim: dim_array[k].clone(),
+                stride: stride_array[k].clone(),
+                attr: attribute_array[k].clone(),
+                hw_access: hw_access_array[k].clone(),
+                volatile: volatile_array[k]
+                    .as_ref()
+                    .map(|volatile| volatile == "true"),
+                reserved: false,
+                resets,
+                enums,
+                desc: description_array[k].clone(),
             })
         })
+        .collect::<anyhow::Result<Vec<_>, Error>>()?;
+
+    Ok(Register {
+        name,
+        offset,
+        size,
+        fields,
+    })
+}
+
+pub fn parse_hex(s: &str) -> anyhow::Result<u64, Error> {
+    let digits = s.trim_start_matches("0x").trim_start_matches("0X");
+    Ok(u64::from_str_radix(digits, 16)?)
+}
+
+pub fn df_to_regs(df: &DataFrame) -> anyhow::Result<Vec<Register>, Error> {
+    let groups = df.column("GROUP_NAME")?.str()?;
+
+    (0..df.height())
+        .filter(|&i| groups.get(i).is_none())
+        .map(|i| df_to_reg(df, i))
         .collect()
 }
 
+pub fn df_to_rfs(df: &DataFrame) -> anyhow::Result<Vec<RegisterFile>, Error> {
+    // keep register files in the order they first appear in the sheet
+    let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+    for (i, group) in df.column("GROUP_NAME")?.str()?.into_iter().enumerate() {
+        let Some(group) = group else { continue };
+        match groups.iter_mut().find(|(name, _)| *name == group) {
+            Some((_, rows)) => rows.push(i),
+            None => groups.push((group, vec![i])),
+        }
+    }
+
+    let mut rfs = Vec::new();
+    for (_, rows) in groups {
+        let regs = rows
+            .iter()
+            .map(|&i| df_to_reg(df, i))
+            .collect::<anyhow::Result<Vec<_>, Error>>()?;
+
+        // a register file starts at its lowest register and ends after its highest one
+        let offsets = regs
+            .iter()
+            .map(|reg| parse_hex(reg.offset()))
+            .collect::<anyhow::Result<Vec<_>, Error>>()?;
+        let base = offsets.iter().copied().min().unwrap_or_default();
+        let mut end = base;
+        for (reg, offset) in regs.iter().zip(&offsets) {
+            end = end.max(offset + reg.size().parse::<u64>()? / 8);
+        }
+        let range = end - base;
+
+        let regs: Vec<Register> = regs
+            .into_iter()
+            .zip(&offsets)
+            .map(|(reg, offset)| Register {
+                offset: format!("0x{:X}", offset - base),
+                ..reg
+            })
+            .collect();
+
+        let spec = df
+            .column("GROUP")?
+            .str()?
+            .get(rows[0])
+            .ok_or_else(|| PolarsError::NoData("No data in DataFrame".into()))?;
+        let pattern = Pattern::parse(spec)?;
+
+        // "ch{n}, n=0~15" maps onto a register file array
+        if let Some((name, dim, stride)) = pattern.as_array(&[])? {
+            let stride = stride.unwrap_or(range);
+            if stride < range {
+                return Err(Error::Expand(
+                    spec.into(),
+                    format!("stride 0x{:X} is smaller than the register file", stride),
+                ));
+            }
+            rfs.push(RegisterFile {
+                name,
+                offset: format!("0x{:X}", base),
+                dim: dim.to_string(),
+                range: format!("0x{:X}", stride),
+                regs,
+            });
+            continue;
+        }
+
+        // everything else is exploded into single register files
+        let mut instances = pattern.instances(range, &[])?;
+        instances.sort_by_key(|instance| instance.offset);
+        if instances
+            .windows(2)
+            .any(|pair| pair[1].offset - pair[0].offset < range)
+        {
+            return Err(Error::Expand(
+                spec.into(),
+                "register file instances overlap".into(),
+            ));
+        }
+        for instance in instances {
+            rfs.push(RegisterFile {
+                name: instance.name,
+                offset: format!("0x{:X}", base + instance.offset),
+                dim: "1".into(),
+                range: format!("0x{:X}", range),
+                regs: regs.clone(),
+            });
+        }
+    }
+
+    Ok(rfs)
+}
+
 pub fn df_to_blks<F>(df: DataFrame, mut registers_extractor: F) -> anyhow::Result<Vec<Block>, Error>
 where
-    F: FnMut(&str) -> anyhow::Result<Vec<Register>, Error>,
+    F: FnMut(&str) -> anyhow::Result<(Vec<Register>, Vec<RegisterFile>), Error>,
 {
     (0..df.height())
         .map(|i| {
@@ -204,7 +900,7 @@ where
                 .map(|s| s.into())
                 .ok_or_else(|| Error::Polars(PolarsError::NoData("No data in DataFrame".into())))?;
             let size = "32".to_string();
-            let regs = registers_extractor(&name)?;
+            let (regs, rfs) = registers_extractor(&name)?;
 
             Ok(Block {
                 name,
@@ -212,6 +908,7 @@ where
                 range,
                 size,
                 regs,
+                rfs,
             })
         })
         .collect()
@@ -245,3 +942,77 @@ where
         blks,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The problems `validate` finds in a single block holding `regs` and `rfs`.
+    fn problems(regs: &str, rfs: &str) -> String {
+        let compo: Component = serde_norway::from_str(&format!(
+            "{{vendor: v, library: l, name: n, version: '1.0', \
+             blks: [{{name: blk, offset: 0, range: 0x100, regs: {}, rfs: {}}}]}}",
+            regs, rfs
+        ))
+        .unwrap();
+        match compo.validate(&AttributeTable::load(None).unwrap()) {
+            Ok(()) => String::new(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn reg(offset: &str, field: &str) -> String {
+        format!(
+            "[{{name: r, offset: {}, fields: [{{name: f, attr: RW, {}}}]}}]",
+            offset, field
+        )
+    }
+
+    const FIELD: &str = "offset: 0, width: 8, resets: [{value: 0}]";
+
+    #[test]
+    fn valid() {
+        assert_eq!(problems(&reg("0x10", FIELD), "[]"), "");
+    }
+
+    #[test]
+    fn offsets_that_overflow() {
+        let regs = reg("0xFFFFFFFFFFFFFFFF", FIELD);
+        assert!(problems(&regs, "[]").contains("blk.r: outside of blk"));
+        let rfs = "[{name: x, offset: 0x10, dim: 18446744073709551615, range: 0x10, regs: []}]";
+        assert!(problems("[]", rfs).contains("blk.x: outside of blk"));
+        let field = "offset: 0, width: 8, dim: 18446744073709551615, stride: 8, \
+                     resets: [{value: 0}]";
+        let regs = reg("0", field);
+        assert!(problems(&regs, "[]").contains("blk.r.f: bits outside of the register"));
+    }
+
+    #[test]
+    fn overlapping_registers() {
+        let regs = "[{name: a, offset: 0x10, fields: [{name: f, offset: 0, width: 32, \
+                    attr: RW, resets: [{value: 0}]}]}, \
+                    {name: b, offset: 0x12, fields: [{name: f, offset: 0, width: 8, \
+                    attr: RW, resets: [{value: 0}]}]}]";
+        assert!(problems(regs, "[]").contains("blk.b: overlaps a"));
+        // `ch` takes 0x10 .. 0x20
+        let rfs = format!(
+            "[{{name: ch, offset: 0x10, dim: 4, range: 0x4, regs: {}}}]",
+            reg("0", FIELD)
+        );
+        assert_eq!(problems(&reg("0xC", FIELD), &rfs), "");
+        assert!(problems(&reg("0x1C", FIELD), &rfs).contains("blk.r: overlaps ch"));
+        let rfs = format!(
+            "[{{name: ch, offset: 0x10, dim: 4, range: 0x10, regs: {}}}]",
+            regs
+        );
+        assert!(problems("[]", &rfs).contains("blk.ch.b: overlaps a"));
+    }
+
+    #[test]
+    fn resets() {
+        let field = "offset: 0, width: 8, resets: []";
+        assert!(problems(&reg("0", field), "[]").contains("blk.r.f: has no reset"));
+        let field = "offset: 0, width: 8, resets: [{domain: warm, value: 0}]";
+        assert!(problems(&reg("0", field), "[]").contains("the first reset must have no domain"));
+    }
+}
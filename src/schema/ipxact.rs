@@ -1,6 +1,9 @@
 use derive_builder::Builder;
+use quick_xml::se::{QuoteLevel, Serializer};
 use serde::{Deserialize, Serialize};
 
+use crate::error::Error;
+
 // Simple IP-XACT 2014 implementation
 
 const IEEE1685_2014_NS: &str = "http://www.accellera.org/XMLSchema/IPXACT/1685-2014";
@@ -30,6 +33,49 @@ pub struct Component {
     version: String,
     #[serde(rename = "ipxact:memoryMaps")]
     memory_maps: MemoryMaps,
+    #[serde(rename = "ipxact:resetTypes", skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    reset_types: Option<ResetTypes>,
+}
+
+impl Component {
+    /// The XML document: the declaration, `comment` if given, and the
+    /// component indented by `indent` spaces per level, or on one line for 0.
+    pub fn to_xml(&self, indent: usize, comment: Option<&str>) -> anyhow::Result<String, Error> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        if let Some(comment) = comment {
+            // `--` may not appear inside a comment, and `---` still holds one
+            // after a single pass
+            let mut comment = comment.to_owned();
+            while comment.contains("--") {
+                comment = comment.replace("--", "- -");
+            }
+            xml.push_str(&format!("<!--\n{}\n-->\n", comment));
+        }
+        let mut ser = Serializer::new(&mut xml);
+        // `>` too, a description may hold `]]>`
+        ser.set_quote_level(QuoteLevel::Partial);
+        if indent > 0 {
+            ser.indent(' ', indent);
+        }
+        self.serialize(ser)?;
+        xml.push('\n');
+        Ok(xml)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into))]
+pub struct ResetTypes {
+    #[serde(rename = "ipxact:resetType")]
+    reset_type: Vec<ResetType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into))]
+pub struct ResetType {
+    #[serde(rename = "ipxact:name")]
+    name: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -64,6 +110,28 @@ pub struct Block {
     width: String,
     #[serde(rename = "ipxact:register")]
     register: Vec<Register>,
+    #[serde(rename = "ipxact:registerFile", skip_serializing_if = "Vec::is_empty")]
+    #[builder(default)]
+    register_file: Vec<RegisterFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into))]
+pub struct RegisterFile {
+    #[serde(rename = "ipxact:name")]
+    name: String,
+    #[serde(rename = "ipxact:description", skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    description: Option<String>,
+    #[serde(rename = "ipxact:dim", skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    dim: Option<String>,
+    #[serde(rename = "ipxact:addressOffset")]
+    address_offset: String,
+    #[serde(rename = "ipxact:range")]
+    range: String,
+    #[serde(rename = "ipxact:register")]
+    register: Vec<Register>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -92,21 +160,65 @@ pub struct Field {
     description: Option<String>,
     #[serde(rename = "ipxact:bitOffset")]
     bit_offset: String,
+    #[serde(rename = "ipxact:resets")]
+    resets: Resets,
     #[serde(rename = "ipxact:bitWidth")]
     bit_width: String,
+    #[serde(rename = "ipxact:volatile", skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    volatile: Option<bool>,
     #[serde(rename = "ipxact:access")]
     access: String,
+    #[serde(
+        rename = "ipxact:enumeratedValues",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[builder(default)]
+    enumerated_values: Option<EnumeratedValues>,
     #[serde(
         rename = "ipxact:modifiedWriteValue",
         skip_serializing_if = "Option::is_none"
     )]
     #[builder(default)]
     modified_write_value: Option<String>,
+    #[serde(
+        rename = "ipxact:writeValueConstraint",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[builder(default)]
+    write_value_constraint: Option<WriteValueConstraint>,
     #[serde(rename = "ipxact:readAction", skip_serializing_if = "Option::is_none")]
     #[builder(default)]
     read_action: Option<String>,
-    #[serde(rename = "ipxact:resets")]
-    resets: Resets,
+    #[serde(rename = "ipxact:testable", skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    testable: Option<bool>,
+    #[serde(rename = "ipxact:reserved", skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    reserved: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into))]
+pub struct EnumeratedValues {
+    #[serde(rename = "ipxact:enumeratedValue")]
+    enumerated_value: Vec<EnumeratedValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into))]
+pub struct EnumeratedValue {
+    #[serde(rename = "ipxact:name")]
+    name: String,
+    #[serde(rename = "ipxact:value")]
+    value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[builder(setter(into))]
+pub struct WriteValueConstraint {
+    #[serde(rename = "ipxact:writeAsRead")]
+    write_as_read: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -119,6 +231,13 @@ pub struct Resets {
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
 #[builder(setter(into))]
 pub struct Reset {
+    // no reference means the predefined HARD reset
+    #[serde(rename = "@resetTypeRef", skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    reset_type_ref: Option<String>,
     #[serde(rename = "ipxact:value")]
     value: String,
+    #[serde(rename = "ipxact:mask", skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    mask: Option<String>,
 }
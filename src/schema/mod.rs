@@ -2,20 +2,109 @@ pub mod attr;
 pub mod base;
 pub mod ipxact;
 pub mod regvue;
+pub mod reserved;
 
-use std::collections::HashMap;
-
-use regex::Regex;
+use std::collections::BTreeMap;
 
 use crate::error::Error;
-use crate::schema::attr::{
-    extract_access_value, extract_modified_write_value, extract_read_action_value,
-};
+use crate::schema::attr::AttributeTable;
+use crate::schema::base::parse_hex;
+
+/// Name of the reset in the DEFAULT column, the one IP-XACT assumes when a
+/// reset has no resetTypeRef.
+pub const DEFAULT_RESET: &str = "HARD";
 
-impl TryFrom<&base::Component> for ipxact::Component {
+impl TryFrom<(&base::Component, &AttributeTable)> for ipxact::Component {
     type Error = Error;
-    fn try_from(base: &base::Component) -> anyhow::Result<Self, Error> {
-        let re = Regex::new(r"^(rsvd|reserved)\d*$")?;
+    fn try_from((base, attrs): (&base::Component, &AttributeTable)) -> anyhow::Result<Self, Error> {
+        // shared by registers of address blocks and register files
+        let register = |reg: &base::Register| -> anyhow::Result<ipxact::Register, Error> {
+            // IP-XACT 2014 has no field arrays
+            let fields = reg
+                .fields()
+                .iter()
+                .map(base::Field::flatten)
+                .collect::<Result<Vec<_>, _>>()?
+                .concat();
+
+            Ok(ipxact::RegisterBuilder::default()
+                .name(reg.name())
+                .address_offset(reg.offset())
+                .size(reg.size())
+                // use iterator to get the array of fields
+                .field(fields.iter().map(|field| -> anyhow::Result<ipxact::Field, Error> {
+                        let attr = attrs.get(field.attr())?;
+                        Ok(ipxact::FieldBuilder::default()
+                            .name(field.name())
+                            .bit_offset(field.offset())
+                            .bit_width(field.width())
+                            // only the non-default values are written
+                            .volatile(field.is_volatile(attrs)?.then_some(true))
+                            // convert attribute to access
+                            .access(attr.access())
+                            .enumerated_values(if field.enums().is_empty() {
+                                None
+                            } else {
+                                Some(
+                                    ipxact::EnumeratedValuesBuilder::default()
+                                        .enumerated_value(
+                                            field
+                                                .enums()
+                                                .iter()
+                                                .map(|value| {
+                                                    ipxact::EnumeratedValueBuilder::default()
+                                                        .name(value.name())
+                                                        .value(value.value())
+                                                        .build()
+                                                })
+                                                .collect::<Result<Vec<_>, _>>()?,
+                                        )
+                                        .build()?,
+                                )
+                            })
+                            // convert attribute to modified_write_value
+                            .modified_write_value(attr.modified_write_value().map(String::from))
+                            // software has to write back what it read
+                            .write_value_constraint(if field.reserved() {
+                                Some(
+                                    ipxact::WriteValueConstraintBuilder::default()
+                                        .write_as_read(true)
+                                        .build()?,
+                                )
+                            } else {
+                                None
+                            })
+                            // convert attribute to read_action
+                            .read_action(attr.read_action().map(String::from))
+                            .testable((!attr.testable()).then_some(false))
+                            .reserved(field.reserved().then_some(true))
+                            .resets(
+                                ipxact::ResetsBuilder::default()
+                                    .reset(
+                                        field
+                                            .resets()
+                                            .iter()
+                                            .map(|reset| {
+                                                ipxact::ResetBuilder::default()
+                                                    .reset_type_ref(
+                                                        reset.domain().map(String::from),
+                                                    )
+                                                    .value(reset.value())
+                                                    .mask(reset.mask().map(String::from))
+                                                    .build()
+                                            })
+                                            .collect::<Result<Vec<_>, _>>()?,
+                                    )
+                                    .build()?,
+                            )
+                            .description(field.desc().to_owned())
+                            .build()?
+                        )
+                    }).collect::<Result<Vec<_>, _>>()?
+                )
+                .build()?
+            )
+        };
 
         let memory_maps = ipxact::MemoryMapsBuilder::default()
             .memory_map(vec![
@@ -28,42 +117,18 @@ impl TryFrom<&base::Component> for ipxact::Component {
                             .range(blk.range())
                             .width(blk.size())
                             // use iterator to get the array of registers
-                            .register(blk.regs().iter().map(|reg| -> anyhow::Result<ipxact::Register, Error> {
-                                    Ok(ipxact::RegisterBuilder::default()
-                                        .name(reg.name())
-                                        .address_offset(reg.offset())
-                                        .size(reg.size())
-                                        // use iterator to get the array of fields
-                                        .field(reg.fields().iter().filter(|field| {
-                                            !re.is_match(field.name())
-                                        }).map(|field| -> anyhow::Result<ipxact::Field, Error> {
-                                                Ok(ipxact::FieldBuilder::default()
-                                                    .name(field.name())
-                                                    .bit_offset(field.offset())
-                                                    .bit_width(field.width())
-                                                    // convert attribute to access
-                                                    .access(extract_access_value(field.attr())?)
-                                                    // convert attribute to modified_write_value
-                                                    .modified_write_value(extract_modified_write_value(field.attr())?)
-                                                    // convert attribute to read_action
-                                                    .read_action(extract_read_action_value(field.attr())?)
-                                                    .resets(
-                                                        ipxact::ResetsBuilder::default()
-                                                            .reset(vec![
-                                                                ipxact::ResetBuilder::default()
-                                                                    .value(field.reset())
-                                                                    .build()?
-                                                            ])
-                                                            .build()?
-                                                    )
-                                                    .description(field.desc().to_owned())
-                                                    .build()?
-                                                )
-                                            }).collect::<Result<Vec<_>, _>>()?
-                                        )
-                                        .build()?
-                                    )
-                                }).collect::<Result<Vec<_>, _>>()?
+                            .register(blk.regs().iter().map(register).collect::<Result<Vec<_>, _>>()?)
+                            .register_file(blk.rfs().iter().map(|rf| -> anyhow::Result<ipxact::RegisterFile, Error> {
+                                Ok(ipxact::RegisterFileBuilder::default()
+                                    .name(rf.name())
+                                    // a single instance is not an array
+                                    .dim((rf.dim() != "1").then(|| rf.dim().to_owned()))
+                                    .address_offset(rf.offset())
+                                    .range(rf.range())
+                                    .register(rf.regs().iter().map(register).collect::<Result<Vec<_>, _>>()?)
+                                    .build()?
+                                )
+                            }).collect::<Result<Vec<_>, _>>()?
                             )
                             .build()?
                         )
@@ -73,19 +138,36 @@ impl TryFrom<&base::Component> for ipxact::Component {
             ])
             .build()?;
 
+        // HARD is predefined, the other domains have to be declared
+        let reset_types = base
+            .reset_types()
+            .into_iter()
+            .map(|name| ipxact::ResetTypeBuilder::default().name(name).build())
+            .collect::<Result<Vec<_>, _>>()?;
+        let reset_types = if reset_types.is_empty() {
+            None
+        } else {
+            Some(
+                ipxact::ResetTypesBuilder::default()
+                    .reset_type(reset_types)
+                    .build()?,
+            )
+        };
+
         Ok(ipxact::ComponentBuilder::default()
             .vendor(base.vendor())
             .library(base.library())
             .name(base.name())
             .version(base.version())
             .memory_maps(memory_maps)
+            .reset_types(reset_types)
             .build()?)
     }
 }
 
-impl TryFrom<&base::Component> for regvue::Document {
+impl TryFrom<(&base::Component, &AttributeTable)> for regvue::Document {
     type Error = Error;
-    fn try_from(base: &base::Component) -> anyhow::Result<Self, Error> {
+    fn try_from((base, attrs): (&base::Component, &AttributeTable)) -> anyhow::Result<Self, Error> {
         Ok(regvue::DocumentBuilder::default()
             .schema(
                 regvue::SchemaBuilder::default()
@@ -103,13 +185,44 @@ impl TryFrom<&base::Component> for regvue::Document {
                             .map(|blk| blk.name().into())
                             .collect::<Vec<_>>(),
                     )
-                    .default_reset(String::from("RS"))
+                    .default_reset(String::from(DEFAULT_RESET))
                     .build()?,
             )
             .elements({
-                let mut elements = HashMap::new();
+                let mut elements = BTreeMap::new();
                 for blk in base.blks() {
                     let blk_name = blk.name();
+                    let mut children =
+                        regvue_registers(&mut elements, attrs, blk_name, blk.regs())?;
+
+                    // RegVue has no arrays, every register file instance is a nested blk
+                    for rf in blk.rfs() {
+                        let dim = rf.dim().parse::<u64>()?;
+                        let offset = parse_hex(rf.offset())?;
+                        let range = parse_hex(rf.range())?;
+                        for n in 0..dim {
+                            let rf_name = if dim == 1 {
+                                rf.name().to_owned()
+                            } else {
+                                format!("{}_{}", rf.name(), n)
+                            };
+                            let block_rf_name = format!("{}.{}", blk_name, rf_name);
+                            let element = regvue::ElementBuilder::default()
+                                .r#type("blk")
+                                .id(&block_rf_name)
+                                .name(rf_name)
+                                .offset(format!("0x{:X}", offset + n * range))
+                                .children(regvue_registers(
+                                    &mut elements,
+                                    attrs,
+                                    &block_rf_name,
+                                    rf.regs(),
+                                )?)
+                                .build()?;
+                            elements.insert(block_rf_name.clone(), element);
+                            children.push(block_rf_name);
+                        }
+                    }
 
                     elements.insert(
                         blk_name.into(),
@@ -117,45 +230,119 @@ impl TryFrom<&base::Component> for regvue::Document {
                             .r#type("blk")
                             .id(blk_name)
                             .name(blk_name)
-                            .children(
-                                blk.regs()
-                                    .iter()
-                                    .map(|reg| format!("{}.{}", blk_name, reg.name()))
-                                    .collect::<Vec<_>>(),
-                            )
+                            .children(children)
                             .build()?,
                     );
-
-                    for reg in blk.regs() {
-                        let reg_name = reg.name();
-                        let block_reg_name = format!("{}.{}", blk_name, reg.name());
-                        let element = regvue::ElementBuilder::default()
-                            .r#type("reg")
-                            .id(&block_reg_name)
-                            .name(reg_name)
-                            .offset(reg.offset().to_owned())
-                            .fields({
-                                let mut fields = Vec::new();
-                                for field in reg.fields() {
-                                    fields.push(
-                                        regvue::FieldBuilder::default()
-                                            .name(field.name())
-                                            .lsb(field.offset().parse::<i32>()?)
-                                            .nbits(field.width().parse::<i32>()?)
-                                            .access(field.attr().to_ascii_lowercase())
-                                            .reset(field.reset().to_owned())
-                                            .doc(field.desc().to_owned())
-                                            .build()?,
-                                    );
-                                }
-                                fields
-                            })
-                            .build()?;
-                        elements.insert(block_reg_name, element);
-                    }
                 }
                 elements
             })
             .build()?)
     }
 }
+
+/// Adds the register elements below `parent` and returns their ids.
+fn regvue_registers(
+    elements: &mut BTreeMap<String, regvue::Element>,
+    attrs: &AttributeTable,
+    parent: &str,
+    regs: &[base::Register],
+) -> anyhow::Result<Vec<String>, Error> {
+    let mut children = Vec::new();
+    for reg in regs {
+        let reg_name = reg.name();
+        let parent_reg_name = format!("{}.{}", parent, reg_name);
+        let element = regvue::ElementBuilder::default()
+            .r#type("reg")
+            .id(&parent_reg_name)
+            .name(reg_name)
+            .offset(reg.offset().to_owned())
+            .fields({
+                let mut fields = Vec::new();
+                for field in reg.fields() {
+                    for field in field.flatten()? {
+                        // unknown codes fail here like they do for IP-XACT
+                        let access = attrs.regvue_access(field.attr())?;
+
+                        // RegVue has no keys for these, tell the reader instead
+                        let mut doc = field.desc().to_owned();
+                        if field.reserved() {
+                            doc.push_str("\n\nReserved.");
+                        }
+                        if field.is_volatile(attrs)? {
+                            doc.push_str("\n\nVolatile, hardware may change this field.");
+                        }
+                        for reset in field.resets() {
+                            let domain = reset.domain().unwrap_or(DEFAULT_RESET);
+                            match reset.mask() {
+                                Some("0x0") => doc
+                                    .push_str(&format!("\n\nUnknown after the {} reset.", domain)),
+                                Some(mask) => doc.push_str(&format!(
+                                    "\n\nOnly the bits in {} of the {} reset value are known.",
+                                    mask, domain
+                                )),
+                                None => {}
+                            }
+                        }
+                        fields.push(
+                            regvue::FieldBuilder::default()
+                                .name(field.name())
+                                .lsb(field.offset().parse::<i32>()?)
+                                .nbits(field.width().parse::<i32>()?)
+                                .access(access)
+                                .reset(regvue_reset(&field)?)
+                                .r#enum(if field.enums().is_empty() {
+                                    None
+                                } else {
+                                    Some(
+                                        field
+                                            .enums()
+                                            .iter()
+                                            .map(|value| {
+                                                regvue::EnumValueBuilder::default()
+                                                    .name(value.name().to_owned())
+                                                    .value(value.value().to_owned())
+                                                    .build()
+                                            })
+                                            .collect::<Result<Vec<_>, _>>()?,
+                                    )
+                                })
+                                .doc(doc)
+                                .build()?,
+                        );
+                    }
+                }
+                fields
+            })
+            .build()?;
+        elements.insert(parent_reg_name.clone(), element);
+        children.push(parent_reg_name);
+    }
+    Ok(children)
+}
+
+/// A plain value, or the values of all reset domains with equal ones sharing
+/// an entry.
+fn regvue_reset(field: &base::Field) -> anyhow::Result<regvue::FieldReset, Error> {
+    if field.resets().len() == 1 {
+        return Ok(regvue::FieldReset::Value(field.reset().to_owned()));
+    }
+    let mut groups: Vec<(&str, Vec<String>)> = Vec::new();
+    for reset in field.resets() {
+        let name = reset.domain().unwrap_or(DEFAULT_RESET).to_owned();
+        match groups.iter_mut().find(|(value, _)| *value == reset.value()) {
+            Some((_, names)) => names.push(name),
+            None => groups.push((reset.value(), vec![name])),
+        }
+    }
+    Ok(regvue::FieldReset::Named(
+        groups
+            .into_iter()
+            .map(|(value, names)| {
+                regvue::ResetBuilder::default()
+                    .value(value.to_owned())
+                    .names(names)
+                    .build()
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    ))
+}
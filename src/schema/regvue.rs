@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -71,6 +71,14 @@ pub struct Reset {
     names: Option<Vec<String>>,
 }
 
+/// A single value, or one value per group of named resets.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum FieldReset {
+    Value(String),
+    Named(Vec<Reset>),
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Builder)]
 #[builder(setter(into))]
 pub struct Field {
@@ -80,13 +88,13 @@ pub struct Field {
     access: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default)]
-    reset: Option<String>,
+    reset: Option<FieldReset>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default)]
     doc: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default)]
-    r#enum: Option<EnumValue>,
+    r#enum: Option<Vec<EnumValue>>,
     // experimental
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default)]
@@ -145,5 +153,6 @@ pub struct Element {
 pub struct Document {
     schema: Schema,
     root: Root,
-    elements: HashMap<String, Element>,
+    // sorted by id, for the same JSON on every run
+    elements: BTreeMap<String, Element>,
 }
@@ -0,0 +1,52 @@
+use clap::ValueEnum;
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+
+use crate::error::Error;
+
+const DEFAULT_PATTERN: &str = r"^(rsvd|reserved)_?\d*$";
+const MARKERS: [&str; 3] = ["RSV", "RSVD", "RESERVED"];
+
+/// What happens to reserved fields in every output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReservedPolicy {
+    /// leave them out
+    #[default]
+    Drop,
+    /// keep them as written in the sheet
+    Keep,
+    /// keep them as read-only fields that reset to zero
+    ReadOnly,
+}
+
+/// Tells reserved fields apart by name or by a reserved marker in ATTRIBUTE.
+#[derive(Debug, Clone)]
+pub struct Reserved {
+    policy: ReservedPolicy,
+    patterns: Vec<Regex>,
+}
+
+impl Reserved {
+    /// `patterns` are matched case-insensitively in addition to
+    /// `rsvd`/`reserved` with an optional number.
+    pub fn new(policy: ReservedPolicy, patterns: &[String]) -> anyhow::Result<Self, Error> {
+        let patterns = std::iter::once(DEFAULT_PATTERN)
+            .chain(patterns.iter().map(String::as_str))
+            .map(|pattern| RegexBuilder::new(pattern).case_insensitive(true).build())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { policy, patterns })
+    }
+
+    pub fn policy(&self) -> ReservedPolicy {
+        self.policy
+    }
+
+    pub fn is_marker(attr: &str) -> bool {
+        MARKERS.contains(&attr.trim().to_ascii_uppercase().as_str())
+    }
+
+    pub fn is_reserved(&self, name: &str, attr: &str) -> bool {
+        Self::is_marker(attr) || self.patterns.iter().any(|re| re.is_match(name))
+    }
+}
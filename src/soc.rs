@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+
+use polars::prelude::*;
+
+use crate::error::Error;
+use crate::schema::base::{Block, Component, parse_hex};
+
+/// One row of the `soc` sheet: an IP instance, the register map it comes from
+/// and its base address.
+#[derive(Debug, Clone)]
+pub struct Instance {
+    name: String,
+    source: PathBuf,
+    base: u64,
+}
+
+impl Instance {
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+}
+
+/// Reads the `soc` sheet, INSTANCE | SOURCE | BASE. SOURCE is any input irgen
+/// reads, relative to `dir`.
+pub fn instances(df: &DataFrame, dir: &Path) -> anyhow::Result<Vec<Instance>, Error> {
+    let names = df.column("INSTANCE")?.str()?;
+    let sources = df.column("SOURCE")?.str()?;
+    let bases = df.column("BASE")?.str()?;
+
+    let mut instances: Vec<Instance> = Vec::new();
+    for i in 0..df.height() {
+        // blank lines between groups of IPs
+        let (Some(name), Some(source), Some(base)) = (names.get(i), sources.get(i), bases.get(i))
+        else {
+            continue;
+        };
+        if instances.iter().any(|instance| instance.name == name) {
+            return Err(Error::Model(format!("instance `{}` is given twice", name)));
+        }
+        instances.push(Instance {
+            name: name.into(),
+            source: dir.join(source),
+            base: parse_hex(base)?,
+        });
+    }
+    Ok(instances)
+}
+
+/// The blocks of all instances, each read by `read` and moved to its base.
+/// Blocks of different instances must not share an address.
+pub fn blocks<F>(instances: &[Instance], mut read: F) -> anyhow::Result<Vec<Block>, Error>
+where
+    F: FnMut(&Path) -> anyhow::Result<Component, Error>,
+{
+    let mut blks = Vec::new();
+    for instance in instances {
+        tracing::debug!(
+            "instance: {} from {}",
+            instance.name,
+            instance.source.display()
+        );
+        let ip = read(&instance.source)?;
+        for blk in ip.blks() {
+            blks.push(blk.relocate(&instance.name, instance.base)?);
+        }
+    }
+
+    let mut spans = blks
+        .iter()
+        .map(|blk| {
+            Ok((
+                parse_hex(blk.offset())?,
+                parse_hex(blk.range())?,
+                blk.name(),
+            ))
+        })
+        .collect::<anyhow::Result<Vec<_>, Error>>()?;
+    spans.sort();
+    let mut collisions = Vec::new();
+    let mut last: Option<(u64, &str)> = None;
+    for (start, range, name) in spans {
+        let end = start + range;
+        match last {
+            Some((last_end, last_name)) if start < last_end => {
+                collisions.push(format!(
+                    "{} at 0x{:X} overlaps {}, which ends at 0x{:X}",
+                    name, start, last_name, last_end
+                ));
+                if end > last_end {
+                    last = Some((end, name));
+                }
+            }
+            _ => last = Some((end, name)),
+        }
+    }
+    if !collisions.is_empty() {
+        for collision in &collisions {
+            tracing::error!("{}", collision);
+        }
+        return Err(Error::Model(collisions.join("; ")));
+    }
+
+    Ok(blks)
+}
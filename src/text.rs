@@ -0,0 +1,70 @@
+use std::fs;
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::error::Error;
+use crate::output;
+use crate::schema::base::Component;
+
+/// The text formats of a register map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Yaml,
+    Json,
+    Toml,
+}
+
+pub fn format(path: &Path) -> Option<Format> {
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "yaml" | "yml" => Some(Format::Yaml),
+        "json" => Some(Format::Json),
+        "toml" => Some(Format::Toml),
+        _ => None,
+    }
+}
+
+/// Whether `path` is a register description this module reads.
+pub fn is_model(path: &Path) -> bool {
+    format(path).is_some()
+}
+
+/// Reads a YAML, JSON or TOML register description, the serde form of
+/// [`Component`]. Unknown keys are rejected, the content is checked by
+/// [`Component::validate`].
+pub fn read_model(path: &Path) -> anyhow::Result<Component, Error> {
+    let text = fs::read_to_string(path)?;
+    match format(path) {
+        Some(Format::Yaml) => Ok(serde_norway::from_str(&text)?),
+        Some(Format::Json) => Ok(serde_json::from_str(&text)?),
+        Some(Format::Toml) => Ok(toml::from_str(&text)?),
+        None => Err(Error::Input(format!(
+            "{} is not a YAML, JSON or TOML file",
+            path.display()
+        ))),
+    }
+}
+
+pub fn to_string(compo: &Component, format: Format) -> anyhow::Result<String, Error> {
+    Ok(match format {
+        Format::Yaml => serde_norway::to_string(compo)?,
+        Format::Json => serde_json::to_string_pretty(compo)?,
+        Format::Toml => toml::to_string_pretty(compo)?,
+    })
+}
+
+/// `compo` in the format picked by the extension of `path`, readable again by
+/// [`read_model`].
+pub fn to_string_for(compo: &Component, path: &Path) -> anyhow::Result<String, Error> {
+    let format = format(path).ok_or_else(|| {
+        Error::Input(format!(
+            "{} is not a YAML, JSON or TOML file",
+            path.display()
+        ))
+    })?;
+    to_string(compo, format)
+}
+
+pub fn write_model(compo: &Component, path: &Path) -> anyhow::Result<(), Error> {
+    output::write(path, &to_string_for(compo, path)?)
+}
@@ -0,0 +1,980 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::schema::attr::{Attribute, AttributeTable};
+use crate::schema::base::{Block, Component, Field, Register, parse_hex};
+
+// VHDL-2008 and PSL
+const KEYWORDS: &str = "\
+    abs access after alias all and architecture array assert assume attribute begin block body \
+    buffer bus case component configuration constant context cover default disconnect downto \
+    else elsif end entity exit fairness file for force function generate generic group guarded \
+    if impure in inertial inout is label library linkage literal loop map mod nand new next \
+    nor not null of on open or others out package parameter port postponed procedure process \
+    property protected pure range record register reject release rem report restrict return \
+    rol ror select sequence severity shared signal sla sll sra srl strong subtype then to \
+    transport type unaffected units until use variable vmode vprop vunit wait when while with \
+    xnor xor";
+
+/// The bus a register bank is a slave on, both with 32-bit data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Bus {
+    /// AXI4-Lite
+    #[default]
+    Axi4Lite,
+    /// Wishbone B4 classic
+    Wishbone,
+}
+
+/// A basic identifier, lower case. VHDL has no case, no leading, trailing or
+/// double underscores.
+fn ident(name: &str) -> String {
+    let mut ident = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            ident.push(c.to_ascii_lowercase());
+        } else if !ident.is_empty() && !ident.ends_with('_') {
+            ident.push('_');
+        }
+    }
+    while ident.ends_with('_') {
+        ident.pop();
+    }
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ident.insert_str(0, "x_");
+    }
+    if KEYWORDS.split_whitespace().any(|keyword| keyword == ident) {
+        ident.push_str("_x");
+    }
+    ident
+}
+
+fn constant(parts: &[&str]) -> String {
+    ident(&parts.join("_")).to_ascii_uppercase()
+}
+
+fn natural(value: u64) -> anyhow::Result<String, Error> {
+    if value > i32::MAX as u64 {
+        return Err(Error::Model(format!(
+            "0x{:X} is larger than a VHDL natural",
+            value
+        )));
+    }
+    Ok(format!("16#{:X}#", value))
+}
+
+/// A `std_logic_vector` literal of `width` bits.
+fn literal(value: u64, width: u64) -> String {
+    if width.is_multiple_of(4) {
+        format!("x\"{:0digits$X}\"", value, digits = (width / 4) as usize)
+    } else {
+        format!("\"{:0width$b}\"", value, width = width as usize)
+    }
+}
+
+fn vector(width: u64) -> String {
+    format!("std_logic_vector({} downto 0)", width - 1)
+}
+
+/// `std_logic` for single bits in records, vectors elsewhere.
+fn element(width: u64) -> String {
+    match width {
+        1 => "std_logic".into(),
+        _ => vector(width),
+    }
+}
+
+fn readable(attr: &Attribute) -> bool {
+    matches!(attr.access(), "read-only" | "read-write" | "read-writeOnce")
+}
+
+fn writable(attr: &Attribute) -> bool {
+    attr.access() != "read-only"
+}
+
+fn ones(width: u64) -> u64 {
+    if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+/// The known bits of the default reset of `field`, with 0 for the others.
+fn reset(field: &Field) -> anyhow::Result<u64, Error> {
+    let width: u64 = field.width().parse()?;
+    let (value, known) = field.resets()[0].bits()?;
+    Ok(value & known & ones(width))
+}
+
+/// Where a field lives and who changes it. A field is stored in a flip-flop
+/// unless only hardware or nothing gives its value. HW_ACCESS says what
+/// hardware does with it, without it hardware reads what software writes
+/// and writes read-only and volatile fields.
+#[derive(Default)]
+struct Mode {
+    stored: bool,
+    hw_read: bool,
+    hw_write: bool,
+}
+
+fn mode(field: &Field, attr: &Attribute, attrs: &AttributeTable) -> anyhow::Result<Mode, Error> {
+    if field.reserved() {
+        return Ok(Mode::default());
+    }
+    let (hw_read, hw_write) = match field.hw_access() {
+        Some(hw_access) => (hw_access.contains('r'), hw_access.contains('w')),
+        None => (
+            attr.access() != "read-only",
+            attr.access() == "read-only" || field.is_volatile(attrs)?,
+        ),
+    };
+    Ok(Mode {
+        stored: attr.access() != "read-only" || attr.read_action().is_some(),
+        hw_read,
+        hw_write,
+    })
+}
+
+/// The elements `reg` adds to the input and output records, `<reg>_<field>`
+/// with `_we` for a hardware write of a stored field and `_rd` or `_wr`
+/// pulses for fields whose reads or writes hardware handles.
+fn elements(
+    reg: &Register,
+    attrs: &AttributeTable,
+) -> anyhow::Result<(Vec<String>, Vec<String>), Error> {
+    let (mut ins, mut outs) = (Vec::new(), Vec::new());
+    for field in reg.fields() {
+        for field in field.flatten()? {
+            let attr = attrs.get(field.attr())?;
+            let mode = mode(&field, attr, attrs)?;
+            let name = ident(&format!("{}_{}", reg.name(), field.name()));
+            let ty = element(field.width().parse()?);
+            if mode.hw_write {
+                ins.push(format!("{} : {};", name, ty));
+                if mode.stored {
+                    ins.push(format!("{}_we : std_logic;", name));
+                }
+            }
+            if mode.stored && mode.hw_read {
+                outs.push(format!("{} : {};", name, ty));
+            }
+            if mode.stored && attr.read_action() == Some("modify") {
+                outs.push(format!("{}_rd : std_logic;", name));
+            }
+            if mode.stored && attr.modified_write_value() == Some("modify") {
+                outs.push(format!("{}_wr : std_logic;", name));
+            }
+        }
+    }
+    Ok((ins, outs))
+}
+
+/// A field of one register instance as the bus sees it.
+struct Slot<'a> {
+    name: String,
+    attr: &'a Attribute,
+    mode: Mode,
+    word: u64,
+    // in the bus word
+    lsb: u64,
+    width: u64,
+    reset: u64,
+    signal: String,
+    // below hw_in and hw_out
+    port: String,
+}
+
+impl Slot<'_> {
+    fn bits(&self, vector: &str) -> String {
+        format!(
+            "{}({} downto {})",
+            vector,
+            self.lsb + self.width - 1,
+            self.lsb
+        )
+    }
+
+    fn input(&self) -> String {
+        match self.width {
+            1 => format!("(0 => hw_in.{})", self.port),
+            _ => format!("hw_in.{}", self.port),
+        }
+    }
+
+    fn once(&self) -> bool {
+        matches!(self.attr.access(), "writeOnce" | "read-writeOnce")
+    }
+}
+
+/// The fields of every register and register file instance of a block, and
+/// the bits of every bus word taken so far.
+struct Slots<'a> {
+    attrs: &'a AttributeTable,
+    slots: Vec<Slot<'a>>,
+    taken: BTreeMap<u64, u64>,
+}
+
+impl<'a> Slots<'a> {
+    fn add(
+        &mut self,
+        path: &str,
+        reg: &Register,
+        offset: u64,
+        signal: &str,
+        port: &str,
+    ) -> anyhow::Result<(), Error> {
+        let size: u64 = reg.size().parse()?;
+        let lane = (offset % 4) * 8;
+        if lane + size > 32 {
+            return Err(Error::Model(format!(
+                "{}: a {}-bit register at 0x{:X} doesn't fit a 32-bit bus word",
+                path, size, offset
+            )));
+        }
+        let word = offset / 4;
+        let bits = ones(size) << lane;
+        let taken = self.taken.entry(word).or_default();
+        if *taken & bits != 0 {
+            return Err(Error::Model(format!("{}: overlaps another register", path)));
+        }
+        *taken |= bits;
+
+        for field in reg.fields() {
+            for field in field.flatten()? {
+                let attr = self.attrs.get(field.attr())?;
+                let (lsb, width): (u64, u64) = (field.offset().parse()?, field.width().parse()?);
+                if lsb + width > size {
+                    return Err(Error::Model(format!(
+                        "{}.{}: does not fit the register",
+                        path,
+                        field.name()
+                    )));
+                }
+                let name = format!("{}_{}", reg.name(), field.name());
+                self.slots.push(Slot {
+                    mode: mode(&field, attr, self.attrs)?,
+                    reset: reset(&field)?,
+                    signal: format!("r_{}", ident(&format!("{}{}", signal, name))),
+                    port: format!("{}{}", port, ident(&name)),
+                    name: format!("{}.{}", path, field.name()),
+                    attr,
+                    word,
+                    lsb: lane + lsb,
+                    width,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The package of constants and record types of every block, and a register
+/// bank entity for every block, with their file names.
+pub fn to_files(
+    compo: &Component,
+    attrs: &AttributeTable,
+    bus: Bus,
+) -> anyhow::Result<Vec<(String, String)>, Error> {
+    let pkg = format!("{}_regs_pkg", ident(compo.name()));
+    let mut lines = vec![
+        format!(
+            "-- Registers of {} {}, generated by irgen",
+            compo.name(),
+            compo.version()
+        ),
+        String::new(),
+        "library ieee;".into(),
+        "use ieee.std_logic_1164.all;".into(),
+        String::new(),
+        format!("package {} is", pkg),
+    ];
+    let mut files = Vec::new();
+    for blk in compo.blks() {
+        package(&mut lines, blk, attrs)?;
+        files.push((
+            entity(compo, &pkg, blk, attrs, bus)?,
+            format!("{}_regs.vhd", ident(blk.name())),
+        ));
+    }
+    lines.push("end package;".into());
+    lines.push(String::new());
+    files.insert(0, (lines.join("\n"), format!("{}.vhd", pkg)));
+    Ok(files)
+}
+
+/// The bits of a byte address in `blk`, at least 3 so there is a word index.
+fn addr_width(blk: &Block) -> anyhow::Result<u64, Error> {
+    let mut end = parse_hex(blk.range())?;
+    for reg in blk.regs() {
+        end = end.max(parse_hex(reg.offset())? + reg.size().parse::<u64>()? / 8);
+    }
+    for rf in blk.rfs() {
+        let dim: u64 = rf.dim().parse()?;
+        end = end.max(parse_hex(rf.offset())? + dim * parse_hex(rf.range())?);
+    }
+    Ok((64 - end.saturating_sub(1).leading_zeros() as u64).max(3))
+}
+
+fn package(
+    lines: &mut Vec<String>,
+    blk: &Block,
+    attrs: &AttributeTable,
+) -> anyhow::Result<(), Error> {
+    let base = parse_hex(blk.offset())?;
+    lines.push(String::new());
+    lines.push(format!("  -- {}", blk.name()));
+    lines.push(format!(
+        "  constant {} : {} := {};",
+        constant(&[blk.name(), "BASE"]),
+        vector(if base > u32::MAX as u64 { 64 } else { 32 }),
+        literal(base, if base > u32::MAX as u64 { 64 } else { 32 })
+    ));
+    lines.push(format!(
+        "  constant {} : natural := {};",
+        constant(&[blk.name(), "RANGE"]),
+        natural(parse_hex(blk.range())?)?
+    ));
+    lines.push(format!(
+        "  constant {} : positive := {};",
+        constant(&[blk.name(), "ADDR_WIDTH"]),
+        addr_width(blk)?
+    ));
+    for reg in blk.regs() {
+        reg_constants(lines, &[blk.name()], reg)?;
+    }
+    for rf in blk.rfs() {
+        lines.push(format!(
+            "  constant {} : natural := {};",
+            constant(&[blk.name(), rf.name(), "OFFSET"]),
+            natural(parse_hex(rf.offset())?)?
+        ));
+        lines.push(format!(
+            "  constant {} : natural := {};",
+            constant(&[blk.name(), rf.name(), "COUNT"]),
+            rf.dim()
+        ));
+        lines.push(format!(
+            "  constant {} : natural := {};",
+            constant(&[blk.name(), rf.name(), "RANGE"]),
+            natural(parse_hex(rf.range())?)?
+        ));
+        for reg in rf.regs() {
+            reg_constants(lines, &[blk.name(), rf.name()], reg)?;
+        }
+    }
+
+    let (mut ins, mut outs) = (Vec::new(), Vec::new());
+    for reg in blk.regs() {
+        let (reg_ins, reg_outs) = elements(reg, attrs)?;
+        ins.extend(reg_ins);
+        outs.extend(reg_outs);
+    }
+    for rf in blk.rfs() {
+        let (mut rf_ins, mut rf_outs) = (Vec::new(), Vec::new());
+        for reg in rf.regs() {
+            let (reg_ins, reg_outs) = elements(reg, attrs)?;
+            rf_ins.extend(reg_ins);
+            rf_outs.extend(reg_outs);
+        }
+        for (elements, dir, block_elements) in
+            [(rf_ins, "in", &mut ins), (rf_outs, "out", &mut outs)]
+        {
+            if elements.is_empty() {
+                continue;
+            }
+            let ty = format!(
+                "{}_hw_{}_t",
+                ident(&format!("{}_{}", blk.name(), rf.name())),
+                dir
+            );
+            record(lines, &ty, &elements);
+            if rf.dim() == "1" {
+                block_elements.push(format!("{} : {};", ident(rf.name()), ty));
+            } else {
+                let array = ty.replace("_t", "_array_t");
+                lines.push(format!(
+                    "  type {} is array (0 to {}) of {};",
+                    array,
+                    rf.dim().parse::<u64>()? - 1,
+                    ty
+                ));
+                block_elements.push(format!("{} : {};", ident(rf.name()), array));
+            }
+        }
+    }
+    for (elements, dir) in [(ins, "in"), (outs, "out")] {
+        if !elements.is_empty() {
+            record(
+                lines,
+                &format!("{}_hw_{}_t", ident(blk.name()), dir),
+                &elements,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The offset and reset of `reg` and the bits of its fields.
+fn reg_constants(
+    lines: &mut Vec<String>,
+    path: &[&str],
+    reg: &Register,
+) -> anyhow::Result<(), Error> {
+    let size: u64 = reg.size().parse()?;
+    let name = |suffix: &[&str]| {
+        let mut parts = path.to_vec();
+        parts.push(reg.name());
+        parts.extend(suffix);
+        constant(&parts)
+    };
+    let mut value = 0;
+    for field in reg.fields() {
+        for field in field.flatten()? {
+            let offset: u64 = field.offset().parse()?;
+            value |= (reset(&field)? << offset) & ones(size);
+        }
+    }
+    lines.push(format!(
+        "  constant {} : natural := {};",
+        name(&["OFFSET"]),
+        natural(parse_hex(reg.offset())?)?
+    ));
+    lines.push(format!(
+        "  constant {} : {} := {};",
+        name(&["RESET"]),
+        vector(size),
+        literal(value, size)
+    ));
+    for field in reg.fields() {
+        for field in field.flatten()? {
+            if field.reserved() {
+                continue;
+            }
+            lines.push(format!(
+                "  constant {} : natural := {};",
+                name(&[field.name(), "LSB"]),
+                field.offset()
+            ));
+            lines.push(format!(
+                "  constant {} : natural := {};",
+                name(&[field.name(), "WIDTH"]),
+                field.width()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn record(lines: &mut Vec<String>, ty: &str, elements: &[String]) {
+    lines.push(String::new());
+    lines.push(format!("  type {} is record", ty));
+    for element in elements {
+        lines.push(format!("    {}", element));
+    }
+    lines.push("  end record;".into());
+}
+
+/// The register bank of `blk`: the bus slave, the field flip-flops and what
+/// the ATTRIBUTE of each field does on a write or read, and the hardware
+/// ports.
+fn entity(
+    compo: &Component,
+    pkg: &str,
+    blk: &Block,
+    attrs: &AttributeTable,
+    bus: Bus,
+) -> anyhow::Result<String, Error> {
+    let name = format!("{}_regs", ident(blk.name()));
+    let addr_width = constant(&[blk.name(), "ADDR_WIDTH"]);
+
+    let mut slots = Slots {
+        attrs,
+        slots: Vec::new(),
+        taken: BTreeMap::new(),
+    };
+    for reg in blk.regs() {
+        let path = format!("{}.{}", blk.name(), reg.name());
+        slots.add(&path, reg, parse_hex(reg.offset())?, "", "")?;
+    }
+    let (mut has_in, mut has_out) = (false, false);
+    for reg in blk.regs() {
+        let (ins, outs) = elements(reg, attrs)?;
+        has_in |= !ins.is_empty();
+        has_out |= !outs.is_empty();
+    }
+    for rf in blk.rfs() {
+        let dim: u64 = rf.dim().parse()?;
+        let (offset, range) = (parse_hex(rf.offset())?, parse_hex(rf.range())?);
+        for n in 0..dim {
+            let (signal, port, rf_path) = if dim == 1 {
+                (
+                    format!("{}_", rf.name()),
+                    format!("{}.", ident(rf.name())),
+                    format!("{}.{}", blk.name(), rf.name()),
+                )
+            } else {
+                (
+                    format!("{}_{}_", rf.name(), n),
+                    format!("{}({}).", ident(rf.name()), n),
+                    format!("{}.{}[{}]", blk.name(), rf.name(), n),
+                )
+            };
+            for reg in rf.regs() {
+                let path = format!("{}.{}", rf_path, reg.name());
+                let reg_offset = offset + n * range + parse_hex(reg.offset())?;
+                slots.add(&path, reg, reg_offset, &signal, &port)?;
+            }
+        }
+        for reg in rf.regs() {
+            let (ins, outs) = elements(reg, attrs)?;
+            has_in |= !ins.is_empty();
+            has_out |= !outs.is_empty();
+        }
+    }
+    let slots = slots.slots;
+
+    let mut ports: Vec<(String, &str, String)> = match bus {
+        Bus::Axi4Lite => {
+            let addr = format!("std_logic_vector({} - 1 downto 0)", addr_width);
+            vec![
+                ("s_axi_aclk".into(), "in", "std_logic".into()),
+                ("s_axi_aresetn".into(), "in", "std_logic".into()),
+                ("s_axi_awaddr".into(), "in", addr.clone()),
+                ("s_axi_awprot".into(), "in", vector(3)),
+                ("s_axi_awvalid".into(), "in", "std_logic".into()),
+                ("s_axi_awready".into(), "out", "std_logic".into()),
+                ("s_axi_wdata".into(), "in", vector(32)),
+                ("s_axi_wstrb".into(), "in", vector(4)),
+                ("s_axi_wvalid".into(), "in", "std_logic".into()),
+                ("s_axi_wready".into(), "out", "std_logic".into()),
+                ("s_axi_bresp".into(), "out", vector(2)),
+                ("s_axi_bvalid".into(), "out", "std_logic".into()),
+                ("s_axi_bready".into(), "in", "std_logic".into()),
+                ("s_axi_araddr".into(), "in", addr),
+                ("s_axi_arprot".into(), "in", vector(3)),
+                ("s_axi_arvalid".into(), "in", "std_logic".into()),
+                ("s_axi_arready".into(), "out", "std_logic".into()),
+                ("s_axi_rdata".into(), "out", vector(32)),
+                ("s_axi_rresp".into(), "out", vector(2)),
+                ("s_axi_rvalid".into(), "out", "std_logic".into()),
+                ("s_axi_rready".into(), "in", "std_logic".into()),
+            ]
+        }
+        Bus::Wishbone => vec![
+            ("clk_i".into(), "in", "std_logic".into()),
+            ("rst_i".into(), "in", "std_logic".into()),
+            ("wb_cyc_i".into(), "in", "std_logic".into()),
+            ("wb_stb_i".into(), "in", "std_logic".into()),
+            ("wb_we_i".into(), "in", "std_logic".into()),
+            (
+                "wb_adr_i".into(),
+                "in",
+                format!("std_logic_vector({} - 1 downto 0)", addr_width),
+            ),
+            ("wb_dat_i".into(), "in", vector(32)),
+            ("wb_sel_i".into(), "in", vector(4)),
+            ("wb_dat_o".into(), "out", vector(32)),
+            ("wb_ack_o".into(), "out", "std_logic".into()),
+        ],
+    };
+    if has_in {
+        ports.push((
+            "hw_in".into(),
+            "in",
+            format!("{}_hw_in_t", ident(blk.name())),
+        ));
+    }
+    if has_out {
+        ports.push((
+            "hw_out".into(),
+            "out",
+            format!("{}_hw_out_t", ident(blk.name())),
+        ));
+    }
+
+    let mut lines = vec![
+        format!(
+            "-- {} registers of {} {}, generated by irgen",
+            blk.name(),
+            compo.name(),
+            compo.version()
+        ),
+        String::new(),
+        "library ieee;".into(),
+        "use ieee.std_logic_1164.all;".into(),
+        "use ieee.numeric_std.all;".into(),
+        String::new(),
+        format!("use work.{}.all;", pkg),
+        String::new(),
+        format!("entity {} is", name),
+        "  port (".into(),
+    ];
+    let pad = ports
+        .iter()
+        .map(|(port, _, _)| port.len())
+        .max()
+        .unwrap_or(0);
+    for (i, (port, dir, ty)) in ports.iter().enumerate() {
+        lines.push(format!(
+            "    {:pad$} : {:3} {}{}",
+            port,
+            dir,
+            ty,
+            if i + 1 == ports.len() { "" } else { ";" },
+            pad = pad
+        ));
+    }
+    lines.push("  );".into());
+    lines.push("end entity;".into());
+    lines.push(String::new());
+    lines.push(format!("architecture rtl of {} is", name));
+    let word = format!("natural range 0 to 2 ** ({} - 2) - 1", addr_width);
+    match bus {
+        Bus::Axi4Lite => {
+            lines.push("  alias clk : std_logic is s_axi_aclk;".into());
+            lines.push(String::new());
+            lines.push("  signal bvalid, rvalid : std_logic;".into());
+        }
+        Bus::Wishbone => {
+            lines.push("  alias clk : std_logic is clk_i;".into());
+            lines.push(String::new());
+            lines.push("  signal ack : std_logic;".into());
+        }
+    }
+    lines.push("  signal rst : std_logic;".into());
+    lines.push("  -- the bus access, by 32-bit word".into());
+    lines.push("  signal wr_en, rd_en : std_logic;".into());
+    lines.push(format!("  signal wr_addr, rd_addr : {};", word));
+    lines.push("  signal wr_data, wr_mask, rd_data : std_logic_vector(31 downto 0);".into());
+    let stored: Vec<&Slot> = slots.iter().filter(|slot| slot.mode.stored).collect();
+    if !stored.is_empty() {
+        lines.push("  -- the fields".into());
+    }
+    for slot in &stored {
+        lines.push(format!(
+            "  signal {} : {};",
+            slot.signal,
+            vector(slot.width)
+        ));
+        if slot.once() {
+            lines.push(format!("  signal {}_done : std_logic;", slot.signal));
+        }
+        if slot.attr.read_action() == Some("modify") {
+            lines.push(format!("  signal {}_rd : std_logic;", slot.signal));
+        }
+        if slot.attr.modified_write_value() == Some("modify") {
+            lines.push(format!("  signal {}_wr : std_logic;", slot.signal));
+        }
+    }
+    lines.push("begin".into());
+
+    let (addr, data, strobe) = match bus {
+        Bus::Axi4Lite => {
+            lines.push("  rst <= not s_axi_aresetn;".into());
+            lines.push(String::new());
+            lines.push(
+                "  -- a write takes its address and data together, a read is answered".into(),
+            );
+            lines.push("  -- the cycle after, both always OKAY".into());
+            lines.push("  wr_en <= s_axi_awvalid and s_axi_wvalid and not bvalid;".into());
+            lines.push("  s_axi_awready <= wr_en;".into());
+            lines.push("  s_axi_wready <= wr_en;".into());
+            lines.push("  s_axi_bvalid <= bvalid;".into());
+            lines.push("  s_axi_bresp <= \"00\";".into());
+            lines.push("  rd_en <= s_axi_arvalid and not rvalid;".into());
+            lines.push(format!(
+                "  rd_addr <= to_integer(unsigned(s_axi_araddr({} - 1 downto 2)));",
+                addr_width
+            ));
+            lines.push("  s_axi_arready <= rd_en;".into());
+            lines.push("  s_axi_rvalid <= rvalid;".into());
+            lines.push("  s_axi_rresp <= \"00\";".into());
+            ("s_axi_awaddr", "s_axi_wdata", "s_axi_wstrb")
+        }
+        Bus::Wishbone => {
+            lines.push("  rst <= rst_i;".into());
+            lines.push(String::new());
+            lines.push("  -- every request is acknowledged the cycle after".into());
+            lines.push("  wr_en <= wb_cyc_i and wb_stb_i and wb_we_i and not ack;".into());
+            lines.push("  rd_en <= wb_cyc_i and wb_stb_i and not wb_we_i and not ack;".into());
+            lines.push("  rd_addr <= wr_addr;".into());
+            lines.push("  wb_ack_o <= ack;".into());
+            ("wb_adr_i", "wb_dat_i", "wb_sel_i")
+        }
+    };
+    lines.push(format!(
+        "  wr_addr <= to_integer(unsigned({}({} - 1 downto 2)));",
+        addr, addr_width
+    ));
+    lines.push(format!("  wr_data <= {};", data));
+    lines.push(String::new());
+    lines.push("  lanes : for i in 0 to 3 generate".into());
+    lines.push(format!(
+        "    wr_mask(8 * i + 7 downto 8 * i) <= (others => {}(i));",
+        strobe
+    ));
+    lines.push("  end generate;".into());
+    lines.push(String::new());
+    match bus {
+        Bus::Axi4Lite => lines.extend(
+            [
+                "  axi : process (clk)",
+                "  begin",
+                "    if rising_edge(clk) then",
+                "      if rst = '1' then",
+                "        bvalid <= '0';",
+                "        rvalid <= '0';",
+                "        s_axi_rdata <= (others => '0');",
+                "      else",
+                "        if wr_en = '1' then",
+                "          bvalid <= '1';",
+                "        elsif s_axi_bready = '1' then",
+                "          bvalid <= '0';",
+                "        end if;",
+                "        if rd_en = '1' then",
+                "          rvalid <= '1';",
+                "          s_axi_rdata <= rd_data;",
+                "        elsif s_axi_rready = '1' then",
+                "          rvalid <= '0';",
+                "        end if;",
+                "      end if;",
+                "    end if;",
+                "  end process;",
+            ]
+            .map(String::from),
+        ),
+        Bus::Wishbone => lines.extend(
+            [
+                "  wishbone : process (clk)",
+                "  begin",
+                "    if rising_edge(clk) then",
+                "      if rst = '1' then",
+                "        ack <= '0';",
+                "        wb_dat_o <= (others => '0');",
+                "      else",
+                "        ack <= wb_cyc_i and wb_stb_i and not ack;",
+                "        if rd_en = '1' then",
+                "          wb_dat_o <= rd_data;",
+                "        end if;",
+                "      end if;",
+                "    end if;",
+                "  end process;",
+            ]
+            .map(String::from),
+        ),
+    }
+
+    if !stored.is_empty() {
+        lines.push(String::new());
+        registers(&mut lines, &stored);
+    }
+
+    lines.push(String::new());
+    lines.push("  read_mux : process (all)".into());
+    lines.push("  begin".into());
+    lines.push("    rd_data <= (others => '0');".into());
+    let reads = words(&slots, |slot| {
+        if !readable(slot.attr) {
+            return None;
+        }
+        let value = if slot.mode.stored {
+            slot.signal.clone()
+        } else if slot.mode.hw_write {
+            slot.input()
+        } else if slot.reset != 0 {
+            literal(slot.reset, slot.width)
+        } else {
+            return None;
+        };
+        Some(vec![format!("{} <= {};", slot.bits("rd_data"), value)])
+    });
+    case(&mut lines, 2, "rd_addr", reads);
+    lines.push("  end process;".into());
+
+    let mut outputs = Vec::new();
+    for slot in &stored {
+        if slot.mode.hw_read {
+            let value = match slot.width {
+                1 => format!("{}(0)", slot.signal),
+                _ => slot.signal.clone(),
+            };
+            outputs.push(format!("  hw_out.{} <= {};", slot.port, value));
+        }
+        for pulse in ["rd", "wr"] {
+            let action = match pulse {
+                "rd" => slot.attr.read_action(),
+                _ => slot.attr.modified_write_value(),
+            };
+            if action == Some("modify") {
+                outputs.push(format!(
+                    "  hw_out.{}_{} <= {}_{};",
+                    slot.port, pulse, slot.signal, pulse
+                ));
+            }
+        }
+    }
+    if !outputs.is_empty() {
+        lines.push(String::new());
+        lines.extend(outputs);
+    }
+    lines.push("end architecture;".into());
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+/// The flip-flops of the stored fields: reset, bus writes, read side effects
+/// and then hardware writes, which win.
+fn registers(lines: &mut Vec<String>, stored: &[&Slot]) {
+    lines.push("  regs : process (clk)".into());
+    lines.push("  begin".into());
+    lines.push("    if rising_edge(clk) then".into());
+    lines.push("      if rst = '1' then".into());
+    for slot in stored {
+        lines.push(format!(
+            "        {} <= {};",
+            slot.signal,
+            literal(slot.reset, slot.width)
+        ));
+        if slot.once() {
+            lines.push(format!("        {}_done <= '0';", slot.signal));
+        }
+        if slot.attr.read_action() == Some("modify") {
+            lines.push(format!("        {}_rd <= '0';", slot.signal));
+        }
+        if slot.attr.modified_write_value() == Some("modify") {
+            lines.push(format!("        {}_wr <= '0';", slot.signal));
+        }
+    }
+    lines.push("      else".into());
+    for slot in stored {
+        if slot.attr.read_action() == Some("modify") {
+            lines.push(format!("        {}_rd <= '0';", slot.signal));
+        }
+        if slot.attr.modified_write_value() == Some("modify") {
+            lines.push(format!("        {}_wr <= '0';", slot.signal));
+        }
+    }
+
+    let writes = words(stored.iter().copied(), |slot| {
+        if !writable(slot.attr) {
+            return None;
+        }
+        let (q, data, mask) = (&slot.signal, slot.bits("wr_data"), slot.bits("wr_mask"));
+        let value = match slot.attr.modified_write_value() {
+            Some("oneToClear") => format!("{} and not ({} and {})", q, data, mask),
+            Some("oneToSet") => format!("{} or ({} and {})", q, data, mask),
+            Some("oneToToggle") => format!("{} xor ({} and {})", q, data, mask),
+            Some("zeroToClear") => format!("{} and ({} or not {})", q, data, mask),
+            Some("zeroToSet") => format!("{} or (not {} and {})", q, data, mask),
+            Some("zeroToToggle") => format!("{} xor (not {} and {})", q, data, mask),
+            Some("clear") => format!("{} and not {}", q, mask),
+            Some("set") => format!("{} or {}", q, mask),
+            _ => format!("({} and not {}) or ({} and {})", q, mask, data, mask),
+        };
+        let mut lines = Vec::new();
+        if slot.once() {
+            lines.push(format!("if {}_done = '0' and (or {}) = '1' then", q, mask));
+            lines.push(format!("  {} <= {};", q, value));
+            lines.push(format!("  {}_done <= '1';", q));
+            lines.push("end if;".into());
+        } else {
+            lines.push(format!("{} <= {};", q, value));
+        }
+        if slot.attr.modified_write_value() == Some("modify") {
+            lines.push(format!("{}_wr <= or {};", q, mask));
+        }
+        Some(lines)
+    });
+    if !writes.is_empty() {
+        lines.push("        if wr_en = '1' then".into());
+        case(lines, 5, "wr_addr", writes);
+        lines.push("        end if;".into());
+    }
+
+    let reads = words(stored.iter().copied(), |slot| {
+        match slot.attr.read_action() {
+            Some("clear") => Some(vec![format!("{} <= (others => '0');", slot.signal)]),
+            Some("set") => Some(vec![format!("{} <= (others => '1');", slot.signal)]),
+            Some("modify") => Some(vec![format!("{}_rd <= '1';", slot.signal)]),
+            _ => None,
+        }
+    });
+    if !reads.is_empty() {
+        lines.push("        if rd_en = '1' then".into());
+        case(lines, 5, "rd_addr", reads);
+        lines.push("        end if;".into());
+    }
+
+    for slot in stored.iter().filter(|slot| slot.mode.hw_write) {
+        lines.push(format!("        if hw_in.{}_we = '1' then", slot.port));
+        lines.push(format!("          {} <= {};", slot.signal, slot.input()));
+        lines.push("        end if;".into());
+    }
+    lines.push("      end if;".into());
+    lines.push("    end if;".into());
+    lines.push("  end process;".into());
+}
+
+/// What `f` gives for the slots, by bus word and with the fields they are
+/// for.
+fn words<'s, 'a: 's>(
+    slots: impl IntoIterator<Item = &'s Slot<'a>>,
+    f: impl Fn(&Slot) -> Option<Vec<String>>,
+) -> BTreeMap<u64, Vec<(String, Vec<String>)>> {
+    let mut words: BTreeMap<u64, Vec<(String, Vec<String>)>> = BTreeMap::new();
+    for slot in slots {
+        if let Some(statements) = f(slot) {
+            words
+                .entry(slot.word)
+                .or_default()
+                .push((slot.name.clone(), statements));
+        }
+    }
+    words
+}
+
+/// A case statement on the word address `addr`, `depth` levels of two spaces
+/// in.
+fn case(
+    lines: &mut Vec<String>,
+    depth: usize,
+    addr: &str,
+    words: BTreeMap<u64, Vec<(String, Vec<String>)>>,
+) {
+    let indent = "  ".repeat(depth);
+    lines.push(format!("{}case {} is", indent, addr));
+    for (word, statements) in words {
+        lines.push(format!("{}  when {} =>  -- 0x{:X}", indent, word, word * 4));
+        for (name, statements) in statements {
+            lines.push(format!("{}    -- {}", indent, name));
+            for statement in statements {
+                lines.push(format!("{}    {}", indent, statement));
+            }
+        }
+    }
+    lines.push(format!("{}  when others =>", indent));
+    lines.push(format!("{}    null;", indent));
+    lines.push(format!("{}end case;", indent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::golden;
+
+    #[test]
+    fn golden_banks() {
+        let (compo, attrs) = golden::model();
+        for bus in [Bus::Axi4Lite, Bus::Wishbone] {
+            let dir = bus.to_possible_value().unwrap();
+            for (text, file) in to_files(&compo, &attrs, bus).unwrap() {
+                golden::check(&format!("vhdl/{}/{}", dir.get_name(), file), &text);
+            }
+        }
+    }
+}
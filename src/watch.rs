@@ -0,0 +1,128 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::args::GenerateArgs;
+use crate::config::{self, Config};
+use crate::error::Error;
+use crate::{commands, csv, input};
+
+/// How long the files have to stay untouched before a run, saving writes
+/// several times.
+const QUIET: Duration = Duration::from_millis(300);
+/// How long to wait for a file an atomic save has moved away.
+const SETTLE: Duration = Duration::from_secs(2);
+
+/// Runs `generate`, and again whenever a file it read changes, until
+/// interrupted. A failed run is reported and leaves the earlier outputs alone.
+pub fn watch(args: &GenerateArgs) -> anyhow::Result<u8, Error> {
+    loop {
+        let mut files = Vec::new();
+        match run(args, &mut files) {
+            Ok(()) => tracing::info!("outputs written, waiting for changes"),
+            Err(err) => tracing::error!("{}", err),
+        }
+        if files.is_empty() {
+            return Err(Error::Input(
+                "no --input given and no irgen.toml with `input` found".into(),
+            ));
+        }
+        wait(&files)?;
+    }
+}
+
+/// One run, `files` are what the next one depends on.
+fn run(args: &GenerateArgs, files: &mut Vec<PathBuf>) -> anyhow::Result<(), Error> {
+    let mut args = args.clone();
+    if let Some(path) = config::path(Some(&args.input.config))? {
+        files.push(path.clone());
+        Config::load(&path)?.apply_generate(&mut args);
+    }
+    files.extend(args.input.model.attributes.iter().map(PathBuf::from));
+
+    let inputs: Vec<PathBuf> = args.input.input.iter().map(PathBuf::from).collect();
+    if inputs.is_empty() {
+        return Ok(());
+    }
+    // the inputs alone when they can't be read yet
+    files.extend(input::sources(&inputs).unwrap_or(inputs));
+
+    commands::generate(&args)?;
+    Ok(())
+}
+
+/// Where a watched file is, and its name; no name for a directory of sheets.
+fn key(path: &Path) -> (PathBuf, Option<OsString>) {
+    let canonical = |dir: &Path| {
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf())
+    };
+    if path.is_dir() {
+        return (canonical(path), None);
+    }
+    (
+        canonical(path.parent().unwrap_or(Path::new("."))),
+        path.file_name().map(OsString::from),
+    )
+}
+
+/// Whether a change of `path` is one of `watched`. Lock files of Excel
+/// (`~$name`) and LibreOffice (`.~lock.name#`) and the temporary files of
+/// atomic saves have names of their own and never match.
+fn matches(watched: &[(PathBuf, Option<OsString>)], path: &Path) -> bool {
+    let (dir, name) = key(path);
+    let Some(name) = name else {
+        return false;
+    };
+    watched.iter().any(|(watched_dir, watched_name)| {
+        *watched_dir == dir
+            && match watched_name {
+                Some(watched_name) => *watched_name == name,
+                None => csv::is_csv(path) && !name.to_string_lossy().starts_with("~$"),
+            }
+    })
+}
+
+/// Blocks until one of `files` changed and settled.
+fn wait(files: &[PathBuf]) -> anyhow::Result<(), Error> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+
+    // the directories, the watch on a file is gone once a save replaces it
+    let watched: Vec<_> = files.iter().map(|file| key(file)).collect();
+    let mut dirs: Vec<&PathBuf> = watched.iter().map(|(dir, _)| dir).collect();
+    dirs.sort();
+    dirs.dedup();
+    for dir in dirs {
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    }
+
+    loop {
+        let Ok(event) = rx.recv() else {
+            return Err(Error::Input("the file watcher stopped".into()));
+        };
+        let event = event?;
+        if !matches!(event.kind, EventKind::Access(_))
+            && event.paths.iter().any(|path| matches(&watched, path))
+        {
+            break;
+        }
+    }
+    while rx.recv_timeout(QUIET).is_ok() {}
+
+    let start = Instant::now();
+    while start.elapsed() < SETTLE && !files.iter().all(|file| file.exists()) {
+        thread::sleep(Duration::from_millis(50));
+    }
+    tracing::info!("change detected, generating again");
+    Ok(())
+}